@@ -58,12 +58,16 @@ macro_rules! bench_floats {
 bench_floats!(
     #[cfg(feature = "inexact")]
     revise_cross_parameters::float::F64Num,
+    #[cfg(feature = "inexact")]
+    revise_cross_parameters::float::ExtF64Num,
     #[cfg(feature = "rug")]
     revise_cross_parameters::float::RugNum,
     #[cfg(feature = "dashu")]
     revise_cross_parameters::float::DashuNum,
     #[cfg(feature = "nightly-float")]
     revise_cross_parameters::float::F128Num,
+    #[cfg(feature = "double-double")]
+    revise_cross_parameters::float::DdNum,
 );
 
 criterion_group! {
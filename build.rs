@@ -0,0 +1,61 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Runs git with `args` in the directory of the crate, returning its trimmed output on success.
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    // outside of a git checkout, e.g. when built from crates.io, the commit is unknown, and so it
+    // is when the crate is vendored in another repository, whose commit would be picked up
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let Some(top) = git(&["rev-parse", "--show-toplevel"]) else {
+        return;
+    };
+    let same_file = |a: &Path, b: &Path| a.canonicalize().ok() == b.canonicalize().ok();
+    if !same_file(
+        &Path::new(&top).join("Cargo.toml"),
+        &manifest_dir.join("Cargo.toml"),
+    ) {
+        return;
+    }
+    let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) else {
+        return;
+    };
+    let git_dir = Path::new(&git_dir);
+
+    // a commit moves the branch HEAD points to, which is either a loose ref or in packed-refs
+    let mut watched = vec![git_dir.join("HEAD")];
+    if let Some(head) = git(&["rev-parse", "--symbolic-full-name", "HEAD"]) {
+        let head = git_dir.join(head);
+        // a packed branch gets a loose ref in its directory with the next commit
+        match head.parent() {
+            Some(dir) if !head.exists() => watched.push(dir.to_path_buf()),
+            _ => watched.push(head),
+        }
+    }
+    watched.push(git_dir.join("packed-refs"));
+    for path in watched.into_iter().filter(|path| path.exists()) {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
+    // the dirty flag only covers the files of the package, and is refreshed with the commit or
+    // the sources
+    if let Some(commit) = git(&["rev-parse", "--short=12", "HEAD"]) {
+        let dirty =
+            git(&["status", "--porcelain", "--", "."]).is_some_and(|status| !status.is_empty());
+        let suffix = if dirty { "-dirty" } else { "" };
+        println!("cargo:rustc-env=REVISE_CROSS_PARAMETERS_COMMIT={commit}{suffix}");
+    }
+}
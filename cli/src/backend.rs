@@ -0,0 +1,42 @@
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use revise_cross_parameters::float::Backend;
+use std::sync::OnceLock;
+
+/// Value of the `--backend` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendArg {
+    /// A single backend
+    Single(Backend),
+    /// Every enabled backend, one after the other
+    All,
+}
+
+impl Default for BackendArg {
+    fn default() -> Self {
+        BackendArg::Single(
+            Backend::most_precise().expect("at least one backend feature must be enabled"),
+        )
+    }
+}
+
+impl ValueEnum for BackendArg {
+    fn value_variants<'a>() -> &'a [Self] {
+        // the backends enabled in the library, which may be more than the features of this crate
+        static VARIANTS: OnceLock<Vec<BackendArg>> = OnceLock::new();
+        VARIANTS.get_or_init(|| {
+            Backend::ENABLED
+                .iter()
+                .map(|&backend| BackendArg::Single(backend))
+                .chain([BackendArg::All])
+                .collect()
+        })
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            BackendArg::Single(backend) => Some(PossibleValue::new(backend.name())),
+            BackendArg::All => Some(PossibleValue::new("all")),
+        }
+    }
+}
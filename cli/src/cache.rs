@@ -0,0 +1,259 @@
+//! On-disk cache of the estimates, so that the expensive parameter sets are computed only once.
+//!
+//! Each estimate is stored as a JSON file in the cache directory, named after its [`Key`]. The
+//! key includes the [`VERSION`] of the crate and the git [`COMMIT`] it was built from, so that the
+//! estimates of other builds are never returned, and are removed when the same estimate is stored
+//! again. The [`ESTIMATOR_VERSION`] is included as well, to discard the estimates of a build whose
+//! commit is unknown or dirty.
+
+use revise_cross_parameters::float::Backend;
+use revise_cross_parameters::provenance::COMMIT;
+use revise_cross_parameters::{Estimate, ESTIMATOR_VERSION, VERSION};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{env, fmt, fs, io};
+
+/// Attack whose optimum is cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    /// The original attack, as estimated by `estimate_attack`.
+    Original,
+    /// Our attack, as estimated by `estimate_attack_new`.
+    New,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Variant::Original => "original",
+            Variant::New => "new",
+        })
+    }
+}
+
+/// Everything an estimate depends on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Key {
+    pub p: i64,
+    pub t: i64,
+    pub w: i64,
+    pub variant: Variant,
+    pub backend: Backend,
+    pub precision: u32,
+    /// Version of the crate that computed the estimate.
+    pub version: String,
+    /// Git commit the crate was built from, if known.
+    pub commit: Option<String>,
+    pub estimator_version: u32,
+}
+
+impl Key {
+    /// Key of the estimate of `variant` with `backend` by the current build.
+    pub fn new(p: i64, t: i64, w: i64, variant: Variant, backend: Backend) -> Self {
+        Key {
+            p,
+            t,
+            w,
+            variant,
+            backend,
+            precision: backend.precision(),
+            version: VERSION.to_string(),
+            commit: COMMIT.map(str::to_string),
+            estimator_version: ESTIMATOR_VERSION,
+        }
+    }
+
+    /// Name of the file without the build.
+    fn stem(&self) -> String {
+        format!(
+            "{}-p{}-t{}-w{}-{}-{}",
+            self.variant, self.p, self.t, self.w, self.backend, self.precision
+        )
+    }
+
+    fn file_name(&self) -> String {
+        format!(
+            "{}-v{}-{}-e{}.json",
+            self.stem(),
+            self.version,
+            self.commit.as_deref().unwrap_or("unknown"),
+            self.estimator_version
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    key: Key,
+    estimate: Estimate,
+}
+
+/// Directory of cached estimates.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Cache { dir: dir.into() }
+    }
+
+    /// Default cache directory, `$XDG_CACHE_HOME/revise_cross_parameters` or
+    /// `$HOME/.cache/revise_cross_parameters`.
+    pub fn default_dir() -> Option<PathBuf> {
+        env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .map(|dir| dir.join("revise_cross_parameters"))
+    }
+
+    /// Cached estimate for `key`, if any.
+    pub fn get(&self, key: &Key) -> Option<Estimate> {
+        let json = fs::read_to_string(self.dir.join(key.file_name())).ok()?;
+        let entry: Entry = serde_json::from_str(&json).ok()?;
+        (entry.key == *key).then_some(entry.estimate)
+    }
+
+    /// Store `estimate` for `key`, removing the entries of the other builds.
+    pub fn insert(&self, key: &Key, estimate: &Estimate) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let stem = format!("{}-v", key.stem());
+        for file in fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            let stale = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&stem) && name != key.file_name());
+            if stale {
+                fs::remove_file(path)?;
+            }
+        }
+
+        // write to a temporary file first, so that concurrent runs never read a partial entry
+        let entry = Entry {
+            key: key.clone(),
+            estimate: estimate.clone(),
+        };
+        let path = self.dir.join(key.file_name());
+        let tmp = path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_string_pretty(&entry)?)?;
+        fs::rename(tmp, path)
+    }
+
+    /// Cached estimate for `key`, or the result of `estimate` which is then stored.
+    ///
+    /// Failures to store the estimate are reported on stderr but are not fatal.
+    pub fn get_or_insert_with(&self, key: &Key, estimate: impl FnOnce() -> Estimate) -> Estimate {
+        if let Some(cached) = self.get(key) {
+            return cached;
+        }
+
+        let estimate = estimate();
+        if let Err(err) = self.insert(key, &estimate) {
+            eprintln!(
+                "Warning: cannot store the estimate in {}: {err}",
+                self.dir.display()
+            );
+        }
+        estimate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty cache directory, unique to the test.
+    fn cache(test: &str) -> Cache {
+        let dir = env::temp_dir().join(format!(
+            "revise_cross_parameters-{test}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        Cache::new(dir)
+    }
+
+    #[test]
+    fn test_get_insert() {
+        let cache = cache("get_insert");
+        let key = Key::new(127, 252, 212, Variant::Original, Backend::ENABLED[0]);
+        let estimate = Estimate::from((40, 128.01));
+        assert_eq!(cache.get(&key), None);
+
+        cache.insert(&key, &estimate).unwrap();
+        assert_eq!(cache.get(&key), Some(estimate.clone()));
+        let cached = cache.get_or_insert_with(&key, || panic!("estimated again"));
+        assert_eq!(cached, estimate);
+
+        // another key is neither returned nor overwritten
+        let other = Key::new(127, 252, 212, Variant::New, Backend::ENABLED[0]);
+        assert_eq!(cache.get(&other), None);
+        let new = cache.get_or_insert_with(&other, || Estimate::from((38, 227, 120.46)));
+        assert_eq!(new, Estimate::from((38, 227, 120.46)));
+        assert_eq!(cache.get(&other), Some(new));
+        assert_eq!(cache.get(&key), Some(estimate));
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn test_key_mismatch() {
+        let cache = cache("key_mismatch");
+        let key = Key::new(127, 252, 212, Variant::Original, Backend::ENABLED[0]);
+        cache.insert(&key, &Estimate::from((40, 128.01))).unwrap();
+
+        // an entry whose content does not match its file name is ignored
+        let json = fs::read_to_string(cache.dir.join(key.file_name())).unwrap();
+        let other = Key {
+            precision: key.precision + 1,
+            ..key.clone()
+        };
+        let json = json.replace(
+            &format!("\"precision\": {}", key.precision),
+            &format!("\"precision\": {}", other.precision),
+        );
+        fs::write(cache.dir.join(key.file_name()), json).unwrap();
+        assert_eq!(cache.get(&key), None);
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn test_stale() {
+        let cache = cache("stale");
+        let key = Key::new(127, 252, 212, Variant::Original, Backend::ENABLED[0]);
+        let stale = [
+            Key {
+                version: "0.0.1".to_string(),
+                ..key.clone()
+            },
+            Key {
+                commit: Some("0123456789ab".to_string()),
+                ..key.clone()
+            },
+            Key {
+                estimator_version: ESTIMATOR_VERSION - 1,
+                ..key.clone()
+            },
+        ];
+        let estimate = Estimate::from((40, 128.01));
+        for stale in &stale {
+            cache.insert(stale, &Estimate::from((40, 128.5))).unwrap();
+            assert_eq!(cache.get(&key), None);
+        }
+
+        // storing the estimate of the current build removes the stale ones
+        cache.insert(&key, &estimate).unwrap();
+        for stale in &stale {
+            assert_eq!(cache.get(stale), None);
+        }
+        assert_eq!(cache.get(&key), Some(estimate));
+        let files = fs::read_dir(&cache.dir).unwrap().count();
+        assert_eq!(files, 1);
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+}
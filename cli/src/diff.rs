@@ -0,0 +1,344 @@
+//! Comparison of two combined reports written by `--jobs --report`.
+//!
+//! The parameter sets are matched by name. For each attack, a change of the cost beyond the
+//! tolerance, of `t*` or of `alpha` is reported, and so is a cost moving across the security
+//! threshold of the NIST category of the parameter set, however small the change.
+
+use revise_cross_parameters::presets;
+use revise_cross_parameters::provenance::describe_build;
+use revise_cross_parameters::Estimate;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Args, Debug)]
+pub struct DiffArgs {
+    /// Report of the previous run
+    old: PathBuf,
+
+    /// Report of the new run
+    new: PathBuf,
+
+    /// Largest difference of the costs in bits that is not a change
+    #[arg(long, default_value_t = 0.005)]
+    tolerance: f64,
+}
+
+/// Combined report, read without checking the backends, which may not be enabled in this build.
+#[derive(Debug, Deserialize)]
+struct ResultFile {
+    version: String,
+    #[serde(default)]
+    commit: Option<String>,
+    jobs: Vec<SetResult>,
+}
+
+impl ResultFile {
+    /// Build of the crate which wrote the report.
+    fn build(&self) -> String {
+        describe_build(&self.version, self.commit.as_deref())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SetResult {
+    name: String,
+    p: i64,
+    t: i64,
+    w: i64,
+    #[serde(default)]
+    category: Option<u8>,
+    backend: String,
+    original: Option<Estimate>,
+    new: Option<Estimate>,
+}
+
+fn load(path: &Path) -> Result<ResultFile, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+    serde_json::from_str(&contents).map_err(|err| format!("cannot parse {}: {err}", path.display()))
+}
+
+/// `old -> new`, or just `old` if both are equal.
+fn transition(old: impl ToString, new: impl ToString) -> String {
+    let (old, new) = (old.to_string(), new.to_string());
+    if old == new {
+        old
+    } else {
+        format!("{old} -> {new}")
+    }
+}
+
+/// Changes of the estimate of an attack, or [`None`] if there are none.
+///
+/// # Arguments
+///
+/// * `old` - Estimate of the previous run, if any.
+/// * `new` - Estimate of the new run, if any.
+/// * `tolerance` - Largest difference of the costs in bits that is not a change.
+/// * `threshold` - Security threshold of the parameter set in bits, if its category is known.
+fn compare(
+    old: Option<&Estimate>,
+    new: Option<&Estimate>,
+    tolerance: f64,
+    threshold: Option<f64>,
+) -> Option<String> {
+    let (old, new) = match (old, new) {
+        (None, None) => return None,
+        (Some(_), None) => return Some("no longer estimated".to_string()),
+        (None, Some(_)) => return Some("newly estimated".to_string()),
+        (Some(old), Some(new)) => (old, new),
+    };
+    let delta = new.cost - old.cost;
+    // NaN costs are changes unless both are NaN
+    let cost_changed = !(delta.abs() <= tolerance || old.cost.is_nan() && new.cost.is_nan());
+    let crossing =
+        threshold.and_then(
+            |threshold| match (old.cost >= threshold, new.cost >= threshold) {
+                (true, false) => Some(format!(" ** now below {threshold} bits **")),
+                (false, true) => Some(format!(" ** now above {threshold} bits **")),
+                _ => None,
+            },
+        );
+    if !cost_changed && crossing.is_none() && old.ts == new.ts && old.alpha == new.alpha {
+        return None;
+    }
+
+    // a crossing within the tolerance needs more decimals to tell the costs apart
+    let decimals = match crossing {
+        Some(_) if !cost_changed => ((-delta.abs().log10()).ceil() as usize).clamp(2, 15),
+        _ => 2,
+    };
+    let mut change = format!(
+        "cost {:.decimals$} -> {:.decimals$} ({:+.decimals$}), t* {}",
+        old.cost,
+        new.cost,
+        delta,
+        transition(old.ts, new.ts)
+    );
+    if let (Some(alpha_old), Some(alpha_new)) = (old.alpha, new.alpha) {
+        change += &format!(", alpha {}", transition(alpha_old, alpha_new));
+    }
+    if let Some(crossing) = crossing {
+        change += &crossing;
+    }
+    Some(change)
+}
+
+/// Print the changes between the reports.
+///
+/// Returns [`true`] if no parameter set changed.
+fn diff(args: &DiffArgs) -> Result<bool, String> {
+    let old = load(&args.old)?;
+    let new = load(&args.new)?;
+    println!("Build: {}", transition(old.build(), new.build()));
+
+    let mut changed = 0;
+    for set in &new.jobs {
+        let Some(previous) = old.jobs.iter().find(|previous| previous.name == set.name) else {
+            println!("{}: added", set.name);
+            changed += 1;
+            continue;
+        };
+
+        let mut changes = Vec::new();
+        if (previous.p, previous.t, previous.w) != (set.p, set.t, set.w) {
+            changes.push(format!(
+                "parameters p = {}, t = {}, w = {} -> p = {}, t = {}, w = {}",
+                previous.p, previous.t, previous.w, set.p, set.t, set.w
+            ));
+        }
+        if previous.category != set.category {
+            let category =
+                |category: Option<u8>| category.map_or("-".to_string(), |c| c.to_string());
+            changes.push(format!(
+                "category {} -> {}",
+                category(previous.category),
+                category(set.category)
+            ));
+        }
+        if previous.backend != set.backend {
+            changes.push(format!("backend {} -> {}", previous.backend, set.backend));
+        }
+        let attacks = [
+            (
+                "original",
+                previous.original.as_ref(),
+                set.original.as_ref(),
+            ),
+            ("new", previous.new.as_ref(), set.new.as_ref()),
+        ];
+        let threshold = set
+            .category
+            .or(previous.category)
+            .and_then(presets::threshold);
+        for (attack, old, new) in attacks {
+            if let Some(change) = compare(old, new, args.tolerance, threshold) {
+                changes.push(format!("{attack} attack: {change}"));
+            }
+        }
+
+        if !changes.is_empty() {
+            println!("{}:", set.name);
+            for change in &changes {
+                println!("  {change}");
+            }
+            changed += 1;
+        }
+    }
+    for previous in &old.jobs {
+        if !new.jobs.iter().any(|set| set.name == previous.name) {
+            println!("{}: removed", previous.name);
+            changed += 1;
+        }
+    }
+
+    if changed == 0 {
+        println!(
+            "No changes in {} parameter sets (tolerance {} bits)",
+            new.jobs.len(),
+            args.tolerance
+        );
+    } else {
+        println!();
+        println!("{changed} parameter sets changed");
+    }
+    Ok(changed == 0)
+}
+
+/// Print the changes between the reports, returning the exit status of `diff`: 0 if no
+/// parameter set changed, 1 if some did and 2 on errors.
+pub fn run(args: &DiffArgs) -> i32 {
+    match diff(args) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    const TOLERANCE: f64 = 0.005;
+
+    #[test]
+    fn test_compare() {
+        let old = Estimate::from((38, 227, 120.46));
+        assert_eq!(
+            compare(Some(&old), Some(&old), TOLERANCE, Some(128.0)),
+            None
+        );
+        assert_eq!(compare(None, None, TOLERANCE, None), None);
+        assert_eq!(
+            compare(Some(&old), None, TOLERANCE, None).unwrap(),
+            "no longer estimated"
+        );
+        assert_eq!(
+            compare(None, Some(&old), TOLERANCE, None).unwrap(),
+            "newly estimated"
+        );
+
+        let within = Estimate::from((38, 227, 120.463));
+        assert_eq!(compare(Some(&old), Some(&within), TOLERANCE, None), None);
+        let beyond = Estimate::from((38, 227, 120.5));
+        assert_eq!(
+            compare(Some(&old), Some(&beyond), TOLERANCE, None).unwrap(),
+            "cost 120.46 -> 120.50 (+0.04), t* 38, alpha 227"
+        );
+        let moved = Estimate::from((39, 226, 120.46));
+        assert_eq!(
+            compare(Some(&old), Some(&moved), TOLERANCE, None).unwrap(),
+            "cost 120.46 -> 120.46 (+0.00), t* 38 -> 39, alpha 227 -> 226"
+        );
+
+        let nan = Estimate::from((0, f64::NAN));
+        assert_eq!(compare(Some(&nan), Some(&nan), TOLERANCE, None), None);
+        assert!(compare(Some(&nan), Some(&Estimate::from((0, 1.0))), TOLERANCE, None).is_some());
+    }
+
+    #[test]
+    fn test_threshold() {
+        let old = Estimate::from((40, 128.01));
+        let new = Estimate::from((40, 127.5));
+        assert_eq!(
+            compare(Some(&old), Some(&new), TOLERANCE, Some(128.0)).unwrap(),
+            "cost 128.01 -> 127.50 (-0.51), t* 40 ** now below 128 bits **"
+        );
+        assert_eq!(
+            compare(Some(&new), Some(&old), TOLERANCE, Some(128.0)).unwrap(),
+            "cost 127.50 -> 128.01 (+0.51), t* 40 ** now above 128 bits **"
+        );
+
+        // only the threshold of the category of the parameter set is highlighted
+        let old = Estimate::from((52, 192.5));
+        let new = Estimate::from((52, 191.5));
+        assert_eq!(
+            compare(Some(&old), Some(&new), TOLERANCE, Some(256.0)).unwrap(),
+            "cost 192.50 -> 191.50 (-1.00), t* 52"
+        );
+        assert_eq!(
+            compare(Some(&old), Some(&new), TOLERANCE, None).unwrap(),
+            "cost 192.50 -> 191.50 (-1.00), t* 52"
+        );
+
+        // a crossing is a change even within the tolerance
+        let old = Estimate::from((40, 127.999));
+        let new = Estimate::from((40, 128.001));
+        assert_eq!(compare(Some(&old), Some(&new), TOLERANCE, None), None);
+        assert_eq!(
+            compare(Some(&old), Some(&new), TOLERANCE, Some(128.0)).unwrap(),
+            "cost 127.999 -> 128.001 (+0.002), t* 40 ** now above 128 bits **"
+        );
+    }
+
+    /// Report of the set `CROSS-R-SDP-1-balanced` whose original attack costs `cost`, written to
+    /// a file unique to the test.
+    fn report(test: &str, cost: f64) -> PathBuf {
+        let path = env::temp_dir().join(format!(
+            "revise_cross_parameters-diff-{test}-{}.json",
+            std::process::id()
+        ));
+        let json = format!(
+            r#"{{
+                "version": "0.1.0",
+                "jobs": [{{
+                    "name": "CROSS-R-SDP-1-balanced",
+                    "p": 127,
+                    "t": 252,
+                    "w": 212,
+                    "category": 1,
+                    "backend": "dd",
+                    "original": {{ "ts": 40, "alpha": null, "cost": {cost} }}
+                }}]
+            }}"#
+        );
+        fs::write(&path, json).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let args = |old: &PathBuf, new: &PathBuf| DiffArgs {
+            old: old.clone(),
+            new: new.clone(),
+            tolerance: TOLERANCE,
+        };
+        let old = report("old", 128.01);
+        let same = report("same", 128.012);
+        let below = report("below", 127.999);
+        let missing = env::temp_dir().join("revise_cross_parameters-diff-missing.json");
+
+        assert_eq!(run(&args(&old, &same)), 0);
+        assert_eq!(run(&args(&old, &below)), 1);
+        assert_eq!(run(&args(&old, &missing)), 2);
+
+        for path in [old, same, below] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
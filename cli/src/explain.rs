@@ -0,0 +1,73 @@
+//! Recipe of the attacks at the optimum of the estimates, printed with `--explain`.
+
+use revise_cross_parameters::explain::{explain, CardS};
+use revise_cross_parameters::float::Float;
+use revise_cross_parameters::scheme::Scheme;
+use revise_cross_parameters::Estimate;
+
+/// Smallest probability of `|S| = j` printed in the distribution.
+const THRESHOLD: f64 = 1e-6;
+
+/// Print the recipe of the attack on `scheme` at the optimum `estimate`, the original attack if
+/// it has no `alpha`.
+pub fn print_plan<T: Float>(scheme: &Scheme, estimate: &Estimate) {
+    let plan = explain::<T>(scheme, estimate.ts, estimate.alpha);
+
+    println!("Attack plan:");
+    println!(
+        "  First phase: guess the first challenge of t* = {} of the {} rounds, recomputing the \
+         first hash until at least {} of them are right",
+        plan.ts, scheme.t, plan.ts
+    );
+    println!(
+        "    succeeds with probability 2^{:.2}, after 2^{:.2} attempts on average",
+        plan.prob_first,
+        plan.first_attempts()
+    );
+    println!(
+        "    guesses {:.2} first challenges right on average, the set S",
+        plan.expected_rounds
+    );
+    let guess = if plan.alpha == scheme.w {
+        format!("w = {} non-zero rounds", plan.alpha)
+    } else {
+        format!(
+            "alpha = {} non-zero rounds, while the actual one has w = {}",
+            plan.alpha, scheme.w
+        )
+    };
+    println!(
+        "  Second phase: guess a second challenge with {}, recomputing the second hash \
+         until the second challenge agrees with the guess outside of S",
+        guess
+    );
+    println!(
+        "    succeeds with probability 2^{:.2} given the first phase, after 2^{:.2} attempts on \
+         average",
+        plan.prob_second,
+        plan.second_attempts()
+    );
+
+    println!("  Distribution of |S| = j:");
+    println!(
+        "    {:>5} {:>18} {:>14}",
+        "j", "given first phase", "given forgery"
+    );
+    let (shown, omitted): (Vec<&CardS>, Vec<&CardS>) = plan
+        .distribution
+        .iter()
+        .partition(|card| card.given_first >= THRESHOLD || card.given_forgery >= THRESHOLD);
+    for card in shown {
+        println!(
+            "    {:>5} {:>18.6} {:>14.6}",
+            card.j, card.given_first, card.given_forgery
+        );
+    }
+    if !omitted.is_empty() {
+        println!(
+            "    ({} values of j with probabilities below {:.0e} omitted)",
+            omitted.len(),
+            THRESHOLD
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Batch estimates of candidate parameter sets listed in a TOML or JSON file.
+//!
+//! Each `[[job]]` of the file names a parameter set, given either by its `p`, `t` and `w` or by
+//! the name of one of the [`presets`], together with the attacks to run (by default both) and the
+//! backend (by default the one of `--backend`). The NIST security category is the one of the
+//! preset, and may be given for the other parameter sets. All the jobs run in parallel, and their estimates
+//! are printed as a table and optionally written as a combined JSON report.
+
+use crate::cache::{Cache, Variant};
+use rayon::prelude::*;
+use revise_cross_parameters::float::Backend;
+use revise_cross_parameters::presets;
+use revise_cross_parameters::provenance::{self, COMMIT};
+use revise_cross_parameters::{Estimate, VERSION};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+/// Contents of a jobs file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobsFile {
+    #[serde(rename = "job")]
+    jobs: Vec<JobSpec>,
+}
+
+/// Job as written in the file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobSpec {
+    /// Default the name of the preset.
+    name: Option<String>,
+    preset: Option<String>,
+    p: Option<i64>,
+    t: Option<i64>,
+    w: Option<i64>,
+    /// Default the category of the preset.
+    category: Option<u8>,
+    #[serde(default = "all_attacks")]
+    attacks: Vec<Variant>,
+    backend: Option<Backend>,
+}
+
+fn all_attacks() -> Vec<Variant> {
+    vec![Variant::Original, Variant::New]
+}
+
+/// Validated job.
+#[derive(Debug, Clone)]
+struct Job {
+    name: String,
+    p: i64,
+    t: i64,
+    w: i64,
+    category: Option<u8>,
+    attacks: Vec<Variant>,
+    backend: Backend,
+}
+
+impl Job {
+    fn from_spec(spec: JobSpec, backend: Backend) -> Result<Self, String> {
+        let (name, p, t, w, category) = match (spec.preset, spec.p, spec.t, spec.w) {
+            (Some(preset), None, None, None) => {
+                let preset =
+                    presets::find(&preset).ok_or_else(|| format!("unknown preset {preset:?}"))?;
+                let name = spec.name.unwrap_or_else(|| preset.name.to_string());
+                let category = spec.category.unwrap_or(preset.category);
+                (name, preset.p, preset.t, preset.w, Some(category))
+            }
+            (Some(_), ..) => return Err("give either a preset or p, t and w".to_string()),
+            (None, Some(p), Some(t), Some(w)) => {
+                let name = spec.name.ok_or("a job without a preset needs a name")?;
+                (name, p, t, w, spec.category)
+            }
+            (None, ..) => return Err("give either a preset or all of p, t and w".to_string()),
+        };
+        revise_cross_parameters::check_params(t, w, p).map_err(|err| format!("{name}: {err}"))?;
+        if let Some(category) = category.filter(|&category| presets::threshold(category).is_none())
+        {
+            return Err(format!("{name}: category {category} is not 1, 3 or 5"));
+        }
+        if spec.attacks.is_empty() {
+            return Err(format!("{name}: no attacks to run"));
+        }
+
+        Ok(Job {
+            name,
+            p,
+            t,
+            w,
+            category,
+            attacks: spec.attacks,
+            backend: spec.backend.unwrap_or(backend),
+        })
+    }
+}
+
+/// Estimates of a job in the combined report.
+#[derive(Debug, Serialize)]
+struct JobReport {
+    name: String,
+    p: i64,
+    t: i64,
+    w: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<u8>,
+    backend: Backend,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original: Option<Estimate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new: Option<Estimate>,
+}
+
+/// Combined report of all the jobs, in the order of the file.
+#[derive(Debug, Serialize)]
+struct Report {
+    version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<&'static str>,
+    jobs: Vec<JobReport>,
+}
+
+/// Read the jobs of `path`, parsed as JSON if its extension is `json` and as TOML otherwise.
+fn load(path: &Path, backend: Backend) -> Result<Vec<Job>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+    let file: JobsFile = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&contents).map_err(|err| err.to_string()),
+        _ => toml::from_str(&contents).map_err(|err| err.to_string()),
+    }
+    .map_err(|err| format!("cannot parse {}: {err}", path.display()))?;
+
+    file.jobs
+        .into_iter()
+        .enumerate()
+        .map(|(i, spec)| {
+            Job::from_spec(spec, backend).map_err(|err| format!("invalid job {}: {err}", i + 1))
+        })
+        .collect()
+}
+
+fn run(job: &Job, cache: Option<&Cache>, quiet: bool) -> JobReport {
+    let start = Instant::now();
+    let estimate = |variant| {
+        job.attacks
+            .contains(&variant)
+            .then(|| crate::estimate_cached(job.p, job.t, job.w, variant, job.backend, cache))
+    };
+    let report = JobReport {
+        name: job.name.clone(),
+        p: job.p,
+        t: job.t,
+        w: job.w,
+        category: job.category,
+        backend: job.backend,
+        original: estimate(Variant::Original),
+        new: estimate(Variant::New),
+    };
+    if !quiet {
+        eprintln!("Estimated {} in {:.1?}", job.name, start.elapsed());
+    }
+    report
+}
+
+/// Print the estimates as a Markdown table, in the format of the results of the README.
+fn print_table(reports: &[JobReport]) {
+    let width = reports
+        .iter()
+        .map(|report| report.name.len())
+        .chain([3])
+        .max()
+        .unwrap();
+    let cost = |estimate: Option<&Estimate>| {
+        estimate.map_or(("-".to_string(), "-".to_string()), |estimate| {
+            (format!("{:.2}", estimate.cost), estimate.ts.to_string())
+        })
+    };
+
+    println!(
+        "| {:<width$} | p   | t   | w   | Backend | Complexity (CROSS) | t\\* | Complexity (Our) | t\\* | alpha |",
+        "Set"
+    );
+    println!(
+        "| {} | --- | --- | --- | ------- | ------------------ | --- | ---------------- | --- | ----- |",
+        "-".repeat(width)
+    );
+    for report in reports {
+        let (cost_cross, ts) = cost(report.original.as_ref());
+        let (cost_our, ts_our) = cost(report.new.as_ref());
+        let alpha = report
+            .new
+            .as_ref()
+            .and_then(|estimate| estimate.alpha)
+            .map_or("-".to_string(), |alpha| alpha.to_string());
+        println!(
+            "| {:<width$} | {:<3} | {:<3} | {:<3} | {:<7} | {:<18} | {:<3} | {:<16} | {:<3} | {:<5} |",
+            report.name,
+            report.p,
+            report.t,
+            report.w,
+            report.backend,
+            cost_cross,
+            ts,
+            cost_our,
+            ts_our,
+            alpha
+        );
+    }
+    println!();
+    println!(
+        "Estimated by {} on {}, searching t* in 0..=t and alpha in w..=t",
+        provenance::build(),
+        provenance::describe_threads(rayon::current_num_threads())
+    );
+}
+
+/// Run the jobs of `path` in parallel with `backend` unless they give their own, print their
+/// estimates and write the combined report to `report` if given.
+pub fn jobs(
+    path: &Path,
+    report: Option<&Path>,
+    backend: Backend,
+    cache: Option<&Cache>,
+    quiet: bool,
+) -> Result<(), String> {
+    let jobs = load(path, backend)?;
+
+    let reports: Vec<JobReport> = jobs.par_iter().map(|job| run(job, cache, quiet)).collect();
+    print_table(&reports);
+
+    match report {
+        Some(report) => write_report(report, reports),
+        None => Ok(()),
+    }
+}
+
+/// Write the combined report of the jobs to `path`, in the JSON read by `diff`.
+fn write_report(path: &Path, reports: Vec<JobReport>) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&Report {
+        version: VERSION,
+        commit: COMMIT,
+        jobs: reports,
+    })
+    .unwrap();
+    fs::write(path, json + "\n").map_err(|err| format!("cannot write {}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Command};
+    use clap::Parser;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn spec(toml: &str) -> JobSpec {
+        toml::from_str(toml).unwrap()
+    }
+
+    /// Path of a file unique to the test.
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "revise_cross_parameters-jobs-{}-{name}",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_from_spec() {
+        let default = Backend::ENABLED[0];
+        let other = *Backend::ENABLED.last().unwrap();
+
+        let job = Job::from_spec(spec(r#"preset = "CROSS-R-SDP-1-fast""#), default).unwrap();
+        assert_eq!(job.name, "CROSS-R-SDP-1-fast");
+        assert_eq!((job.p, job.t, job.w), (127, 163, 85));
+        assert_eq!(job.category, Some(1));
+        assert_eq!(job.attacks, all_attacks());
+        assert_eq!(job.backend, default);
+
+        let job = Job::from_spec(
+            spec(&format!(
+                r#"
+                name = "candidate"
+                p = 127
+                t = 24
+                w = 16
+                attacks = ["new"]
+                backend = "{other}"
+                "#
+            )),
+            default,
+        )
+        .unwrap();
+        assert_eq!(job.name, "candidate");
+        assert_eq!((job.p, job.t, job.w), (127, 24, 16));
+        assert_eq!(job.category, None);
+        assert_eq!(job.attacks, vec![Variant::New]);
+        assert_eq!(job.backend, other);
+
+        let error = |toml: &str| Job::from_spec(spec(toml), default).unwrap_err();
+        assert_eq!(
+            error(
+                r#"
+                preset = "CROSS-R-SDP-1-fast"
+                p = 127
+                "#
+            ),
+            "give either a preset or p, t and w"
+        );
+        assert_eq!(
+            error("p = 127\nt = 24\nw = 16"),
+            "a job without a preset needs a name"
+        );
+        assert_eq!(
+            error("name = \"candidate\"\np = 127\nt = 24"),
+            "give either a preset or all of p, t and w"
+        );
+        assert_eq!(
+            error(
+                r#"
+                preset = "CROSS-R-SDP-1-fast"
+                category = 2
+                "#
+            ),
+            "CROSS-R-SDP-1-fast: category 2 is not 1, 3 or 5"
+        );
+        assert_eq!(
+            error(
+                r#"
+                preset = "CROSS-R-SDP-1-fast"
+                attacks = []
+                "#
+            ),
+            "CROSS-R-SDP-1-fast: no attacks to run"
+        );
+        assert_eq!(
+            error("name = \"candidate\"\np = 127\nt = 24\nw = 25"),
+            "candidate: w must be between 0 and t"
+        );
+    }
+
+    #[test]
+    fn test_load() {
+        let backend = Backend::ENABLED[0];
+        let toml = temp_path("load.toml");
+        fs::write(
+            &toml,
+            r#"
+            [[job]]
+            preset = "CROSS-R-SDP-1-fast"
+
+            [[job]]
+            name = "candidate"
+            p = 127
+            t = 24
+            w = 16
+            category = 1
+            "#,
+        )
+        .unwrap();
+        let json = temp_path("load.json");
+        fs::write(
+            &json,
+            r#"{
+                "job": [
+                    { "preset": "CROSS-R-SDP-1-fast" },
+                    { "name": "candidate", "p": 127, "t": 24, "w": 16, "category": 1 }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        for path in [&toml, &json] {
+            let jobs = load(path, backend).unwrap();
+            let sets: Vec<_> = jobs
+                .iter()
+                .map(|job| (job.name.as_str(), job.p, job.t, job.w, job.category))
+                .collect();
+            assert_eq!(
+                sets,
+                [
+                    ("CROSS-R-SDP-1-fast", 127, 163, 85, Some(1)),
+                    ("candidate", 127, 24, 16, Some(1)),
+                ]
+            );
+        }
+
+        // a TOML file is not read as JSON
+        fs::copy(&toml, &json).unwrap();
+        assert!(load(&json, backend)
+            .unwrap_err()
+            .starts_with(&format!("cannot parse {}", json.display())));
+
+        for path in [toml, json] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let report = |name: &str, cost: f64| {
+            let path = temp_path(name);
+            let job = JobReport {
+                name: "CROSS-R-SDP-1-balanced".to_string(),
+                p: 127,
+                t: 252,
+                w: 212,
+                category: Some(1),
+                backend: Backend::ENABLED[0],
+                original: Some(Estimate::from((40, cost))),
+                new: Some(Estimate::from((38, 227, 120.46))),
+            };
+            write_report(&path, vec![job]).unwrap();
+            path
+        };
+        let diff = |old: &Path, new: &Path| {
+            let cli = Cli::try_parse_from([
+                "revise_cross_parameters_cli".as_ref(),
+                "diff".as_ref(),
+                old.as_os_str(),
+                new.as_os_str(),
+            ])
+            .unwrap();
+            let Some(Command::Diff(args)) = cli.command else {
+                unreachable!()
+            };
+            crate::diff::run(&args)
+        };
+
+        // the reports written by the jobs are read back by diff
+        let old = report("old.json", 128.01);
+        let same = report("same.json", 128.01);
+        let below = report("below.json", 127.99);
+        assert_eq!(diff(&old, &same), 0);
+        assert_eq!(diff(&old, &below), 1);
+
+        for path in [old, same, below] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -1,16 +1,84 @@
-use clap::Parser;
+use backend::BackendArg;
+use cache::{Cache, Key, Variant};
+use clap::{Parser, Subcommand};
 use rayon::ThreadPoolBuilder;
-#[cfg(feature = "nightly-float")]
-use revise_cross_parameters::float::F128Num as Float;
-#[cfg(all(not(feature = "nightly-float"), feature = "inexact"))]
-use revise_cross_parameters::float::F64Num as Float;
-#[cfg(all(not(feature = "nightly-float"), not(feature = "inexact")))]
-use revise_cross_parameters::float::RugNum as Float;
-use revise_cross_parameters::{estimate_attack, estimate_attack_new};
+use resume::Resume;
+use revise_cross_parameters::bound::{self, Bounded};
+use revise_cross_parameters::composition::{self, Composition};
+use revise_cross_parameters::float::{Backend, Float};
+use revise_cross_parameters::provenance::{self, Provenance};
+use revise_cross_parameters::scheme::Scheme;
+use revise_cross_parameters::{with_backend, Estimate};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::{Duration, Instant};
+
+mod backend;
+mod cache;
+mod diff;
+mod explain;
+mod jobs;
+mod plot;
+mod resume;
+mod serve;
+mod toy;
+mod verify;
 
 #[derive(Parser, Debug)]
-#[command(version, long_about = None)]
-struct Args {
+#[command(
+    version,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    params: Option<Params>,
+
+    /// File of parameter sets to estimate in parallel (TOML, or JSON with a .json extension)
+    #[arg(long, conflicts_with_all = ["p", "t", "w", "resume", "explain"])]
+    jobs: Option<PathBuf>,
+
+    /// Write the combined report of --jobs to this JSON file
+    #[arg(long, requires = "jobs", conflicts_with_all = ["p", "t", "w"])]
+    report: Option<PathBuf>,
+
+    /// Number of threads (default all)
+    #[arg(long, global = true)]
+    threads: Option<usize>,
+
+    /// Do not show a progress bar
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// Float implementation, or all of them side by side (default the most precise)
+    #[arg(long, global = true)]
+    backend: Option<BackendArg>,
+
+    /// Directory of the cached estimates (default $XDG_CACHE_HOME/revise_cross_parameters)
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// Neither read nor store cached estimates
+    #[arg(long, global = true, conflicts_with = "cache_dir")]
+    no_cache: bool,
+
+    /// Checkpoint file of the estimates, resumed if it exists
+    #[arg(long, global = true)]
+    resume: Option<PathBuf>,
+
+    /// Print the attack plan at the optimum of each estimate
+    #[arg(long, global = true)]
+    explain: bool,
+}
+
+#[derive(clap::Args, Debug, Clone, Copy)]
+struct Params {
     /// Prime order of finite field Fp
     #[arg(short)]
     p: i64,
@@ -22,38 +90,588 @@ struct Args {
     /// Fixed-weight parameter for the second challenge
     #[arg(short)]
     w: i64,
+}
 
-    /// Number of threads (default all)
-    #[structopt(long)]
-    threads: Option<usize>,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Render SVG plots of the cost curves
+    Plot(plot::PlotArgs),
+    /// Forge signatures on a toy CROSS instance
+    Toy(toy::ToyArgs),
+    /// Estimate the attacks on a generic scheme with a fixed-weight second challenge
+    Scheme(SchemeArgs),
+    /// Check every enabled backend against the exact values and the most precise one
+    Verify(verify::VerifyArgs),
+    /// Serve the estimates as JSON over HTTP
+    Serve(serve::ServeArgs),
+    /// Compare two reports of --jobs, failing if any parameter set changed
+    Diff(diff::DiffArgs),
+}
 
-    /// Do not show a progress bar
-    #[structopt(long)]
+#[derive(clap::Args, Debug)]
+struct SchemeArgs {
+    /// Number of parallel repetitions
+    #[arg(short, required_unless_present = "counts")]
+    t: Option<i64>,
+
+    /// Number of rounds with a non-zero second challenge
+    #[arg(short, required_unless_present = "counts")]
+    w: Option<i64>,
+
+    /// Size of the first challenge space of a single round
+    #[arg(short = 'q', long)]
+    first_challenges: i64,
+
+    /// Number of values of the second challenge of a single round
+    #[arg(short = 'n', long, default_value_t = 2)]
+    second_challenges: i64,
+
+    /// Number of rounds with each value of the second challenge (e.g. 40,20,20), replacing -t, -w and -n
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["t", "w", "second_challenges"]
+    )]
+    counts: Option<Vec<i64>>,
+}
+
+/// Parameter set to estimate, as given on the command line.
+enum Target {
+    Cross(Params),
+    Scheme(Scheme),
+    Composition(Composition),
+}
+
+impl Target {
+    fn from_args(args: &SchemeArgs) -> Result<Self, String> {
+        match &args.counts {
+            Some(counts) => {
+                Composition::new(args.first_challenges, counts.clone()).map(Target::Composition)
+            }
+            None => {
+                let scheme = Scheme {
+                    t: args.t.unwrap(),
+                    w: args.w.unwrap(),
+                    first_challenges: args.first_challenges,
+                    second_challenges: args.second_challenges,
+                };
+                scheme.check().map(|()| Target::Scheme(scheme))
+            }
+        }
+    }
+
+    /// Name of the guess for the second challenge of our attack.
+    fn guess_name(&self) -> &'static str {
+        match self {
+            Target::Cross(_) | Target::Scheme(_) => "alpha",
+            Target::Composition(_) => "guess composition",
+        }
+    }
+
+    /// Number of parallel rounds.
+    fn t(&self) -> i64 {
+        match self {
+            Target::Cross(params) => params.t,
+            Target::Scheme(scheme) => scheme.t,
+            Target::Composition(composition) => composition.t(),
+        }
+    }
+
+    /// Scheme of the target, unless it is a composition.
+    fn scheme(&self) -> Option<Scheme> {
+        match self {
+            Target::Cross(Params { p, t, w }) => Some(Scheme::cross(*p, *t, *w)),
+            Target::Scheme(scheme) => Some(*scheme),
+            Target::Composition(_) => None,
+        }
+    }
+
+    /// Provenance of an estimate with `backend` which took `elapsed`, searching `alpha` if `new`
+    /// unless the target is a composition.
+    fn provenance(&self, backend: Backend, new: bool, elapsed: Duration) -> Provenance {
+        let alpha_range = match self.scheme() {
+            Some(scheme) if new => Some(scheme.w..=scheme.t),
+            _ => None,
+        };
+        Provenance::new(backend, 0..=self.t(), alpha_range, elapsed)
+    }
+
+    /// Bound on the error in bits of the cost of the original attack, or ours if `new`, guessing
+    /// `ts` first challenges with `T`.
+    fn cost_bound<T: Float>(&self, ts: i64, new: bool) -> f64 {
+        match self {
+            Target::Composition(composition) => {
+                let prob_beta = composition::prob_beta::<Bounded<T>>(composition, ts);
+                let prob_b = if new {
+                    composition::prob_b_new::<Bounded<T>>(composition, ts).1
+                } else {
+                    composition::prob_b::<Bounded<T>>(composition, ts)
+                };
+                bound::bits(&(Bounded::from(1) / prob_beta + Bounded::from(1) / prob_b))
+            }
+            _ => {
+                let scheme = self.scheme().unwrap();
+                if new {
+                    bound::attack_new_cost::<T>(&scheme, ts)
+                } else {
+                    bound::attack_cost::<T>(&scheme, ts)
+                }
+            }
+        }
+    }
+
+    /// Estimate of the original attack, with its provenance.
+    fn original<T: Float + Serialize + DeserializeOwned>(
+        &self,
+        resume: Option<&mut Resume<T>>,
+        quiet: bool,
+    ) -> Estimate {
+        let start = Instant::now();
+        let mut estimate = match (self, resume) {
+            (_, Some(resume)) => resume.original(quiet).into(),
+            (Target::Cross(_) | Target::Scheme(_), None) => {
+                Estimate::attack::<T>(&self.scheme().unwrap(), quiet)
+            }
+            (Target::Composition(composition), None) => {
+                composition::estimate_attack::<T>(composition, quiet).into()
+            }
+        };
+        estimate
+            .provenance
+            .get_or_insert_with(|| self.provenance(T::BACKEND, false, start.elapsed()));
+        estimate
+    }
+
+    /// Estimate of our attack, with its provenance and its guess for the second challenge.
+    fn ours<T: Float + Serialize + DeserializeOwned>(
+        &self,
+        resume: Option<&mut Resume<T>>,
+        quiet: bool,
+    ) -> (Estimate, String) {
+        let start = Instant::now();
+        let (mut estimate, guess) = match (self, resume) {
+            (_, Some(resume)) => {
+                let (ts, aa, comp) = resume.ours(quiet);
+                ((ts, aa, comp).into(), aa.to_string())
+            }
+            (Target::Cross(_) | Target::Scheme(_), None) => {
+                let estimate = Estimate::attack_new::<T>(&self.scheme().unwrap(), quiet);
+                let alpha = estimate.alpha.unwrap().to_string();
+                (estimate, alpha)
+            }
+            (Target::Composition(composition), None) => {
+                let (ts, guess, comp) = composition::estimate_attack_new::<T>(composition, quiet);
+                ((ts, comp).into(), format!("{:?}", guess))
+            }
+        };
+        estimate
+            .provenance
+            .get_or_insert_with(|| self.provenance(T::BACKEND, true, start.elapsed()));
+        (estimate, guess)
+    }
+
+    /// As [`Target::original`], but looking up the estimate of the CROSS parameter sets in
+    /// `cache` and storing it if missing.
+    fn original_cached<T: Float + Serialize + DeserializeOwned>(
+        &self,
+        backend: Backend,
+        cache: Option<&Cache>,
+        resume: Option<&mut Resume<T>>,
+        quiet: bool,
+    ) -> Estimate {
+        match (self, cache) {
+            (Target::Cross(Params { p, t, w }), Some(cache)) => {
+                let key = Key::new(*p, *t, *w, Variant::Original, backend);
+                cache.get_or_insert_with(&key, || self.original(resume, quiet))
+            }
+            _ => self.original(resume, quiet),
+        }
+    }
+
+    /// As [`Target::ours`], but looking up the estimate of the CROSS parameter sets in `cache`
+    /// and storing it if missing.
+    fn ours_cached<T: Float + Serialize + DeserializeOwned>(
+        &self,
+        backend: Backend,
+        cache: Option<&Cache>,
+        resume: Option<&mut Resume<T>>,
+        quiet: bool,
+    ) -> (Estimate, String) {
+        match (self, cache) {
+            (Target::Cross(Params { p, t, w }), Some(cache)) => {
+                let key = Key::new(*p, *t, *w, Variant::New, backend);
+                let estimate = cache.get_or_insert_with(&key, || self.ours(resume, quiet).0);
+                let alpha = estimate
+                    .alpha
+                    .expect("cached estimate of our attack without alpha");
+                (estimate, alpha.to_string())
+            }
+            _ => self.ours(resume, quiet),
+        }
+    }
+}
+
+/// Print the provenance of `estimate`, unless it was cached by a build which did not record it.
+fn print_provenance(estimate: &Estimate) {
+    if let Some(provenance) = &estimate.provenance {
+        println!("Estimated by {}", provenance);
+    }
+}
+
+/// Warn on stderr if the error bound of a cost affects its two reported decimals.
+fn check_bound(attack: &str, bound: f64) {
+    if bound.is_nan() || bound >= 0.005 {
+        eprintln!(
+            "Warning: the rounding error of {} attack may reach {:.2e} bits, beyond the reported \
+             decimals; consider a more precise backend",
+            attack, bound
+        );
+    }
+}
+
+fn estimate<T: Float + Serialize + DeserializeOwned>(
+    backend: Backend,
+    target: &Target,
+    cache: Option<&Cache>,
+    resume: Option<&Path>,
+    explain: bool,
     quiet: bool,
+) {
+    let mut resume = resume.map(|path| {
+        let scheme = target
+            .scheme()
+            .expect("--resume is not supported for compositions");
+        Resume::<T>::open(path, scheme, backend)
+    });
+
+    println!("Estimating complexity of original attack...");
+    let original = target.original_cached::<T>(backend, cache, resume.as_mut(), quiet);
+    let bound_cross = target.cost_bound::<T>(original.ts, false);
+    println!(
+        "Original attack has a cost of {:.2} ± {:.2e} bits",
+        original.cost, bound_cross
+    );
+    println!("Original attack is optimized for t* = {}", original.ts);
+    print_provenance(&original);
+    check_bound("the original", bound_cross);
+    if explain {
+        let scheme = target
+            .scheme()
+            .expect("--explain is not supported for compositions");
+        explain::print_plan::<T>(&scheme, &original);
+    }
+
+    println!();
+
+    println!("Estimating complexity of our attack...");
+    let (ours, guess) = target.ours_cached::<T>(backend, cache, resume.as_mut(), quiet);
+    let bound_our = target.cost_bound::<T>(ours.ts, true);
+    println!(
+        "Our attack has a cost of {:.2} ± {:.2e} bits",
+        ours.cost, bound_our
+    );
+    println!(
+        "Our attack is optimized for t* = {} and {} = {}",
+        ours.ts,
+        target.guess_name(),
+        guess
+    );
+    print_provenance(&ours);
+    check_bound("our", bound_our);
+    if explain {
+        let scheme = target
+            .scheme()
+            .expect("--explain is not supported for compositions");
+        explain::print_plan::<T>(&scheme, &ours);
+    }
+}
+
+/// Check that `--resume` (if `resume`) and `--explain` (if `explain`) are supported for `target`
+/// with `backend`.
+fn check_options(
+    target: &Target,
+    backend: BackendArg,
+    resume: bool,
+    explain: bool,
+) -> Result<(), String> {
+    for (option, enabled) in [("--resume", resume), ("--explain", explain)] {
+        if !enabled {
+            continue;
+        }
+        if matches!(target, Target::Composition(_)) {
+            return Err(format!("{option} is not supported with --counts"));
+        }
+        if backend == BackendArg::All {
+            return Err(format!("{option} is not supported with --backend all"));
+        }
+    }
+    Ok(())
+}
+
+/// Estimate of `variant` for the CROSS parameters `p`, `t` and `w` with `backend`, looked up in
+/// `cache` and stored if missing, without a progress bar.
+fn estimate_cached(
+    p: i64,
+    t: i64,
+    w: i64,
+    variant: Variant,
+    backend: Backend,
+    cache: Option<&Cache>,
+) -> Estimate {
+    let estimate = || {
+        let scheme = Scheme::cross(p, t, w);
+        with_backend!(backend, T => match variant {
+            Variant::Original => Estimate::attack::<T>(&scheme, true),
+            Variant::New => Estimate::attack_new::<T>(&scheme, true),
+        })
+    };
+    match cache {
+        Some(cache) => cache.get_or_insert_with(&Key::new(p, t, w, variant, backend), estimate),
+        None => estimate(),
+    }
+}
+
+/// Largest difference between the finite costs in `costs`.
+fn discrepancy(costs: impl Iterator<Item = f64>) -> f64 {
+    let (min, max) = costs
+        .filter(|comp| comp.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), comp| {
+            (min.min(comp), max.max(comp))
+        });
+    if min <= max {
+        max - min
+    } else {
+        0.0
+    }
+}
+
+fn estimate_all(target: &Target, cache: Option<&Cache>, quiet: bool) {
+    let mut rows = Vec::new();
+    for &backend in Backend::ENABLED {
+        println!("Estimating both attacks with the {} backend...", backend);
+        let (original, ours) = with_backend!(backend, T => (
+            target.original_cached::<T>(backend, cache, None, quiet),
+            target.ours_cached::<T>(backend, cache, None, quiet),
+        ));
+        rows.push((backend, original, ours));
+    }
+
+    println!();
+    println!(
+        "{:<8} {:>4} {:>16} {:>5} {:>16} {:>5} {:>9}  {}",
+        "Backend",
+        "Bits",
+        "Original (bits)",
+        "t*",
+        "Our (bits)",
+        "t*",
+        "Time (s)",
+        target.guess_name()
+    );
+    for (backend, original, (ours, guess)) in &rows {
+        // cached estimates keep the time of the run which computed them
+        let time = match (&original.provenance, &ours.provenance) {
+            (Some(original), Some(ours)) => format!("{:.2}", original.seconds + ours.seconds),
+            _ => "-".to_string(),
+        };
+        println!(
+            "{:<8} {:>4} {:>16.8} {:>5} {:>16.8} {:>5} {:>9}  {}",
+            backend,
+            backend.precision(),
+            original.cost,
+            original.ts,
+            ours.cost,
+            ours.ts,
+            time,
+            guess
+        );
+    }
+
+    println!();
+    println!(
+        "Maximum discrepancy of {:.2e} bits for the original attack and {:.2e} bits for our attack",
+        discrepancy(rows.iter().map(|(_, original, _)| original.cost)),
+        discrepancy(rows.iter().map(|(_, _, (ours, _))| ours.cost))
+    );
+    let alpha_range = match target.scheme() {
+        Some(scheme) => format!(" and alpha in {}..={}", scheme.w, scheme.t),
+        None => String::new(),
+    };
+    println!(
+        "Estimated by {} on {}, searching t* in 0..={}{}",
+        provenance::build(),
+        provenance::describe_threads(rayon::current_num_threads()),
+        target.t(),
+        alpha_range
+    );
 }
 
 fn main() {
-    let args = Args::parse();
+    let cli = Cli::parse();
 
-    if let Some(threads) = args.threads {
+    if let Some(threads) = cli.threads {
         ThreadPoolBuilder::new()
             .num_threads(threads)
             .build_global()
             .unwrap();
     }
 
-    println!("Estimating complexity of original attack...");
-    let (ts, comp_cross) = estimate_attack::<Float>(args.t, args.w, args.p, args.quiet);
-    println!("Original attack has a cost of {:.2} bits", comp_cross);
-    println!("Original attack is optimized for t* = {}", ts);
+    let backend = cli.backend.unwrap_or_default();
 
-    println!();
+    let cache = match (cli.no_cache, cli.cache_dir) {
+        (true, _) => None,
+        (false, dir) => dir.or_else(Cache::default_dir).map(Cache::new),
+    };
 
-    println!("Estimating complexity of our attack...");
-    let (ts_our, aa, comp_our) = estimate_attack_new::<Float>(args.t, args.w, args.p, args.quiet);
-    println!("Our attack has a cost of {:.2} bits", comp_our);
-    println!(
-        "Our attack is optimized for t* = {} and alpha = {}",
-        ts_our, aa
-    );
+    if let Some(path) = &cli.jobs {
+        let BackendArg::Single(backend) = backend else {
+            eprintln!("Error: --backend all is not supported with --jobs");
+            exit(1);
+        };
+        if let Err(err) = jobs::jobs(
+            path,
+            cli.report.as_deref(),
+            backend,
+            cache.as_ref(),
+            cli.quiet,
+        ) {
+            eprintln!("Error: {err}");
+            exit(1);
+        }
+        return;
+    }
+
+    let target = match (&cli.command, &cli.params) {
+        (Some(Command::Scheme(args)), _) => match Target::from_args(args) {
+            Ok(target) => target,
+            Err(err) => {
+                eprintln!("Error: {err}");
+                exit(1);
+            }
+        },
+        (Some(Command::Verify(args)), _) => {
+            if !verify::verify(args, cli.quiet) {
+                exit(1);
+            }
+            return;
+        }
+        (Some(Command::Diff(args)), _) => exit(diff::run(args)),
+        (Some(Command::Serve(args)), _) => {
+            if let Err(err) = serve::serve(args, cache, cli.quiet) {
+                eprintln!("Error: {err}");
+                exit(1);
+            }
+            return;
+        }
+        (None, Some(params)) => {
+            if let Err(err) = revise_cross_parameters::check_params(params.t, params.w, params.p) {
+                eprintln!("Error: {err}");
+                exit(1);
+            }
+            Target::Cross(*params)
+        }
+        (Some(command), _) => {
+            let BackendArg::Single(backend) = backend else {
+                eprintln!("Error: --backend all is only supported by the estimates");
+                exit(1);
+            };
+            with_backend!(backend, T => match command {
+                Command::Plot(args) => {
+                    if let Err(err) = plot::plot::<T>(args, cli.quiet) {
+                        eprintln!("Error: {err}");
+                        exit(1);
+                    }
+                }
+                Command::Toy(args) => {
+                    if let Err(err) = toy::toy::<T>(args, cli.quiet) {
+                        eprintln!("Error: {err}");
+                        exit(1);
+                    }
+                }
+                Command::Scheme(_) | Command::Verify(_) | Command::Serve(_) | Command::Diff(_) => {
+                    unreachable!()
+                }
+            });
+            return;
+        }
+        (None, None) => unreachable!("clap requires -p, -t and -w without a subcommand or --jobs"),
+    };
+
+    let cache = cache.as_ref();
+
+    let resume = cli.resume.as_deref();
+    if let Err(err) = check_options(&target, backend, resume.is_some(), cli.explain) {
+        eprintln!("Error: {err}");
+        exit(1);
+    }
+
+    match backend {
+        BackendArg::Single(backend) => {
+            with_backend!(backend, T => estimate::<T>(backend, &target, cache, resume, cli.explain, cli.quiet))
+        }
+        BackendArg::All => estimate_all(&target, cache, cli.quiet),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_options() {
+        let cross = Target::Cross(Params {
+            p: 127,
+            t: 24,
+            w: 16,
+        });
+        let composition = Target::Composition(Composition::new(126, vec![20, 10, 4]).unwrap());
+        let single = BackendArg::Single(Backend::most_precise().unwrap());
+
+        assert_eq!(check_options(&cross, single, true, true), Ok(()));
+        assert_eq!(
+            check_options(&composition, BackendArg::All, false, false),
+            Ok(())
+        );
+        assert_eq!(
+            check_options(&composition, single, false, true),
+            Err("--explain is not supported with --counts".to_string())
+        );
+        assert_eq!(
+            check_options(&cross, BackendArg::All, false, true),
+            Err("--explain is not supported with --backend all".to_string())
+        );
+        assert_eq!(
+            check_options(&composition, single, true, false),
+            Err("--resume is not supported with --counts".to_string())
+        );
+        assert_eq!(
+            check_options(&cross, BackendArg::All, true, false),
+            Err("--resume is not supported with --backend all".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = |t, w, first_challenges, second_challenges, counts| SchemeArgs {
+            t: Some(t),
+            w: Some(w),
+            first_challenges,
+            second_challenges,
+            counts,
+        };
+
+        assert!(matches!(
+            Target::from_args(&args(24, 16, 126, 4, None)),
+            Ok(Target::Scheme(_))
+        ));
+        assert!(matches!(
+            Target::from_args(&args(0, 0, 126, 2, Some(vec![20, 10, 4]))),
+            Ok(Target::Composition(_))
+        ));
+        assert!(Target::from_args(&args(-1, 0, 126, 2, None)).is_err());
+        assert!(Target::from_args(&args(24, 25, 126, 2, None)).is_err());
+        assert!(Target::from_args(&args(24, 16, 1, 2, None)).is_err());
+        assert!(Target::from_args(&args(24, 16, 126, 1, None)).is_err());
+        assert!(Target::from_args(&args(0, 0, 0, 2, Some(vec![20, 10, 4]))).is_err());
+    }
 }
@@ -0,0 +1,170 @@
+use crate::Params;
+use plotters::prelude::*;
+use revise_cross_parameters::float::Float;
+use revise_cross_parameters::sweep::{attack_costs, attack_new_alpha_costs, attack_new_costs};
+use revise_cross_parameters::{estimate_attack, estimate_attack_new};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SIZE: (u32, u32) = (800, 600);
+
+#[derive(clap::Args, Debug)]
+pub struct PlotArgs {
+    #[command(flatten)]
+    params: Params,
+
+    /// Directory where the SVG files are written
+    #[arg(long, default_value = "plots")]
+    out_dir: PathBuf,
+
+    /// Smallest weight of the cost vs w sweep (default t/2)
+    #[arg(long)]
+    w_min: Option<i64>,
+
+    /// Largest weight of the cost vs w sweep (default t)
+    #[arg(long)]
+    w_max: Option<i64>,
+
+    /// Step of the cost vs w sweep (default covers the range in about 32 steps)
+    #[arg(long)]
+    w_step: Option<i64>,
+}
+
+/// Render the cost curves for the given parameters as SVG files in `args.out_dir`.
+///
+/// The following files are produced:
+///
+/// * `cost_ts.svg` - cost of both attacks as a function of t*;
+/// * `cost_alpha.svg` - cost of our attack as a function of alpha, at its optimal t*;
+/// * `cost_w.svg` - optimal cost of both attacks as a function of w, for fixed t;
+/// * `comparison.svg` - bits saved by our attack over the original one, as a function of w.
+pub fn plot<T: Float>(args: &PlotArgs, quiet: bool) -> Result<(), Box<dyn Error>> {
+    let Params { p, t, w } = args.params;
+    fs::create_dir_all(&args.out_dir)?;
+
+    println!("Computing cost vs t*...");
+    let original = attack_costs::<T>(t, w, p, quiet);
+    let ours = attack_new_costs::<T>(t, w, p, quiet);
+    let ours_ts: Vec<(i64, f64)> = ours.iter().map(|&(ts, _, comp)| (ts, comp)).collect();
+    line_chart(
+        &args.out_dir.join("cost_ts.svg"),
+        &format!("Cost vs t* (p = {p}, t = {t}, w = {w})"),
+        "t*",
+        "cost (bits)",
+        &[("Original attack", &original), ("Our attack", &ours_ts)],
+    )?;
+
+    println!("Computing cost vs alpha...");
+    let &(ts_best, _, _) = ours
+        .iter()
+        .filter(|(_, _, comp)| comp.is_finite())
+        .min_by(|(_, _, comp_a), (_, _, comp_b)| comp_a.total_cmp(comp_b))
+        .ok_or("no finite cost for any t*")?;
+    let alpha = attack_new_alpha_costs::<T>(t, ts_best, w, p, quiet);
+    line_chart(
+        &args.out_dir.join("cost_alpha.svg"),
+        &format!("Cost vs alpha (p = {p}, t = {t}, w = {w}, t* = {ts_best})"),
+        "alpha",
+        "cost (bits)",
+        &[("Our attack", &alpha)],
+    )?;
+
+    println!("Computing cost vs w...");
+    let w_min = args.w_min.unwrap_or(t / 2).max(0);
+    let w_max = args.w_max.unwrap_or(t).min(t);
+    let w_step = args.w_step.unwrap_or(((w_max - w_min) / 32).max(1)).max(1);
+    let (original_w, ours_w): (Vec<_>, Vec<_>) = (w_min..=w_max)
+        .step_by(w_step as usize)
+        .map(|w| {
+            let (_, comp_cross) = estimate_attack::<T>(t, w, p, quiet);
+            let (_, _, comp_our) = estimate_attack_new::<T>(t, w, p, quiet);
+            ((w, comp_cross), (w, comp_our))
+        })
+        .unzip();
+    line_chart(
+        &args.out_dir.join("cost_w.svg"),
+        &format!("Cost vs w (p = {p}, t = {t})"),
+        "w",
+        "cost (bits)",
+        &[("Original attack", &original_w), ("Our attack", &ours_w)],
+    )?;
+
+    let saving: Vec<(i64, f64)> = original_w
+        .iter()
+        .zip(&ours_w)
+        .map(|(&(w, comp_cross), &(_, comp_our))| (w, comp_cross - comp_our))
+        .collect();
+    line_chart(
+        &args.out_dir.join("comparison.svg"),
+        &format!("Original vs our attack (p = {p}, t = {t})"),
+        "w",
+        "saving (bits)",
+        &[("Original - our", &saving)],
+    )?;
+
+    println!("Plots written to {}", args.out_dir.display());
+
+    Ok(())
+}
+
+fn line_chart(
+    path: &Path,
+    caption: &str,
+    x_desc: &str,
+    y_desc: &str,
+    series: &[(&str, &[(i64, f64)])],
+) -> Result<(), Box<dyn Error>> {
+    // infinite costs (zero probabilities) cannot be drawn
+    let points = || {
+        series
+            .iter()
+            .flat_map(|(_, data)| data.iter())
+            .filter(|(_, y)| y.is_finite())
+    };
+    let x_min = points().map(|&(x, _)| x).min().ok_or("nothing to plot")?;
+    let x_max = points().map(|&(x, _)| x).max().unwrap();
+    let y_min = points().map(|&(_, y)| y).fold(f64::INFINITY, f64::min);
+    let y_max = points().map(|&(_, y)| y).fold(f64::NEG_INFINITY, f64::max);
+    let y_margin = ((y_max - y_min) * 0.05).max(0.5);
+
+    let root = SVGBackend::new(path, SIZE).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(caption, ("sans-serif", 24))
+        .margin(16)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(
+            x_min..x_max.max(x_min + 1),
+            (y_min - y_margin)..(y_max + y_margin),
+        )?;
+
+    chart
+        .configure_mesh()
+        .x_desc(x_desc)
+        .y_desc(y_desc)
+        .draw()?;
+
+    for (idx, (label, data)) in series.iter().enumerate() {
+        let color = Palette99::pick(idx).to_rgba();
+        chart
+            .draw_series(LineSeries::new(
+                data.iter().copied().filter(|(_, y)| y.is_finite()),
+                color.stroke_width(2),
+            ))?
+            .label(*label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+
+    Ok(())
+}
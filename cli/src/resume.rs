@@ -0,0 +1,132 @@
+//! Checkpoint file of `--resume`, so that an interrupted estimate only evaluates the missing `t*`.
+//!
+//! The file holds a [`Checkpoint`] as JSON. It is written at most every [`SAVE_INTERVAL`] while
+//! estimating, and once more at the end, always through a temporary file so that an interruption
+//! never leaves a partial checkpoint behind.
+
+use revise_cross_parameters::checkpoint::Checkpoint;
+use revise_cross_parameters::float::{Backend, Float};
+use revise_cross_parameters::scheme::Scheme;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{fs, io};
+
+/// Minimum time between two writes of the checkpoint file.
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Estimates of a scheme backed by a checkpoint file.
+pub struct Resume<T> {
+    path: PathBuf,
+    checkpoint: Checkpoint<T>,
+}
+
+impl<T: Float + Serialize + DeserializeOwned> Resume<T> {
+    /// Resume the checkpoint in `path` if it was computed for `scheme` with `backend` by this
+    /// version, or start from scratch.
+    pub fn open(path: &Path, scheme: Scheme, backend: Backend) -> Self {
+        let checkpoint = match fs::read_to_string(path) {
+            Ok(json) => match load(&json, &scheme, backend) {
+                Ok(Some(checkpoint)) => {
+                    eprintln!(
+                        "Resuming from {} with {} values of t* for the original attack and {} \
+                         for our attack",
+                        path.display(),
+                        checkpoint.original.len(),
+                        checkpoint.new.len()
+                    );
+                    Some(checkpoint)
+                }
+                Ok(None) => {
+                    eprintln!(
+                        "Warning: {} is a checkpoint of other parameters, backend or version; \
+                         starting from scratch",
+                        path.display()
+                    );
+                    None
+                }
+                Err(err) => {
+                    eprintln!(
+                        "Warning: cannot read the checkpoint {}: {err}; starting from scratch",
+                        path.display()
+                    );
+                    None
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => {
+                eprintln!(
+                    "Error: cannot read the checkpoint {}: {err}",
+                    path.display()
+                );
+                std::process::exit(1);
+            }
+        };
+
+        Resume {
+            path: path.to_path_buf(),
+            checkpoint: checkpoint.unwrap_or_else(|| Checkpoint::new(scheme, backend)),
+        }
+    }
+
+    /// As [`scheme::estimate_attack`](revise_cross_parameters::scheme::estimate_attack), saving
+    /// the progress to the checkpoint file.
+    pub fn original(&mut self, quiet: bool) -> (i64, f64) {
+        let mut last = Instant::now();
+        let path = &self.path;
+        let result = self.checkpoint.estimate_attack(quiet, |checkpoint| {
+            if last.elapsed() >= SAVE_INTERVAL {
+                save(path, checkpoint);
+                last = Instant::now();
+            }
+        });
+        save(&self.path, &self.checkpoint);
+        result
+    }
+
+    /// As [`scheme::estimate_attack_new`](revise_cross_parameters::scheme::estimate_attack_new),
+    /// saving the progress to the checkpoint file.
+    pub fn ours(&mut self, quiet: bool) -> (i64, i64, f64) {
+        let mut last = Instant::now();
+        let path = &self.path;
+        let result = self.checkpoint.estimate_attack_new(quiet, |checkpoint| {
+            if last.elapsed() >= SAVE_INTERVAL {
+                save(path, checkpoint);
+                last = Instant::now();
+            }
+        });
+        save(&self.path, &self.checkpoint);
+        result
+    }
+}
+
+/// Parse the checkpoint in `json`, or [`None`] if it cannot be resumed for `scheme` with
+/// `backend`.
+fn load<T: DeserializeOwned>(
+    json: &str,
+    scheme: &Scheme,
+    backend: Backend,
+) -> serde_json::Result<Option<Checkpoint<T>>> {
+    // the costs of another backend cannot be parsed as T, so check the rest first
+    let header: Checkpoint<IgnoredAny> = serde_json::from_str(json)?;
+    if !header.matches(scheme, backend) {
+        return Ok(None);
+    }
+    serde_json::from_str(json).map(Some)
+}
+
+/// Write `checkpoint` to `path`, reporting failures on stderr as they are not fatal.
+fn save<T: Serialize>(path: &Path, checkpoint: &Checkpoint<T>) {
+    let write = || -> io::Result<()> {
+        let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_string(checkpoint)?)?;
+        fs::rename(tmp, path)
+    };
+    if let Err(err) = write() {
+        eprintln!(
+            "Warning: cannot save the checkpoint {}: {err}",
+            path.display()
+        );
+    }
+}
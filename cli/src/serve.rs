@@ -0,0 +1,372 @@
+//! Local HTTP server answering estimates as JSON.
+//!
+//! `POST /estimate` queues an estimate and returns its job, whose status is then polled with
+//! `GET /jobs/<id>` until it is `done` (with the estimate) or `failed` (with the error).
+//! `GET /jobs` lists every job. A single worker runs the jobs in order, each on the global rayon
+//! pool sized by `--threads`, and looks the estimates up in the cache first.
+
+use crate::cache::{Cache, Variant};
+use revise_cross_parameters::float::Backend;
+use revise_cross_parameters::Estimate;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(clap::Args, Debug)]
+pub struct ServeArgs {
+    /// Address to listen on (port 0 picks a free port)
+    #[arg(long, default_value = "127.0.0.1:8000")]
+    addr: String,
+}
+
+/// Body of `POST /estimate`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EstimateRequest {
+    p: i64,
+    t: i64,
+    w: i64,
+    variant: Variant,
+    /// Default the most precise backend.
+    backend: Option<Backend>,
+}
+
+/// Validated parameters of a job.
+#[derive(Debug, Clone, Copy, Serialize)]
+struct JobParams {
+    p: i64,
+    t: i64,
+    w: i64,
+    variant: Variant,
+    backend: Backend,
+}
+
+impl TryFrom<EstimateRequest> for JobParams {
+    type Error = String;
+
+    fn try_from(request: EstimateRequest) -> Result<Self, String> {
+        let EstimateRequest {
+            p,
+            t,
+            w,
+            variant,
+            backend,
+        } = request;
+        revise_cross_parameters::check_params(t, w, p)?;
+
+        let backend = backend
+            .or_else(Backend::most_precise)
+            .ok_or("no backend enabled")?;
+        Ok(JobParams {
+            p,
+            t,
+            w,
+            variant,
+            backend,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Job {
+    id: usize,
+    status: Status,
+    params: JobParams,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimate: Option<Estimate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Jobs indexed by id.
+type Jobs = Arc<Mutex<Vec<Job>>>;
+
+type JsonResponse = Response<Cursor<Vec<u8>>>;
+
+fn estimate(params: JobParams, cache: Option<&Cache>) -> Estimate {
+    let JobParams {
+        p,
+        t,
+        w,
+        variant,
+        backend,
+    } = params;
+    crate::estimate_cached(p, t, w, variant, backend, cache)
+}
+
+/// Run the jobs sent to the returned channel one after the other.
+fn spawn_worker(jobs: Jobs, cache: Option<Cache>, quiet: bool) -> Sender<usize> {
+    let (sender, receiver) = mpsc::channel::<usize>();
+    thread::spawn(move || {
+        for id in receiver {
+            let params = {
+                let mut jobs = jobs.lock().unwrap();
+                jobs[id].status = Status::Running;
+                jobs[id].params
+            };
+            if !quiet {
+                eprintln!("Running job {id}: {params:?}");
+            }
+
+            let result = catch_unwind(AssertUnwindSafe(|| estimate(params, cache.as_ref())));
+
+            let mut jobs = jobs.lock().unwrap();
+            match result {
+                Ok(estimate) => {
+                    jobs[id].status = Status::Done;
+                    jobs[id].estimate = Some(estimate);
+                }
+                Err(panic) => {
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|message| message.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "the estimate panicked".to_string());
+                    jobs[id].status = Status::Failed;
+                    jobs[id].error = Some(message);
+                }
+            }
+        }
+    });
+    sender
+}
+
+/// JSON response with `status`.
+fn json(status: u16, body: &impl Serialize) -> JsonResponse {
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    Response::from_data(serde_json::to_vec_pretty(body).unwrap())
+        .with_status_code(status)
+        .with_header(header)
+}
+
+fn error(status: u16, message: impl Into<String>) -> JsonResponse {
+    #[derive(Serialize)]
+    struct Error {
+        error: String,
+    }
+
+    json(
+        status,
+        &Error {
+            error: message.into(),
+        },
+    )
+}
+
+fn handle(request: &mut Request, jobs: &Jobs, worker: &Sender<usize>) -> JsonResponse {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let segments: Vec<_> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (request.method(), segments.as_slice()) {
+        (Method::Post, ["estimate"]) => {
+            let mut body = String::new();
+            if let Err(err) = request.as_reader().read_to_string(&mut body) {
+                return error(400, format!("cannot read the request: {err}"));
+            }
+            let params = match serde_json::from_str::<EstimateRequest>(&body)
+                .map_err(|err| err.to_string())
+                .and_then(JobParams::try_from)
+            {
+                Ok(params) => params,
+                Err(err) => return error(400, err),
+            };
+
+            let job = {
+                let mut jobs = jobs.lock().unwrap();
+                let job = Job {
+                    id: jobs.len(),
+                    status: Status::Queued,
+                    params,
+                    estimate: None,
+                    error: None,
+                };
+                jobs.push(job.clone());
+                job
+            };
+            // the worker only stops if it panicked outside of an estimate
+            if worker.send(job.id).is_err() {
+                let mut jobs = jobs.lock().unwrap();
+                jobs[job.id].status = Status::Failed;
+                jobs[job.id].error = Some("the worker is not running".to_string());
+                return error(503, "the worker is not running");
+            }
+
+            let location = Header::from_bytes("Location", format!("/jobs/{}", job.id)).unwrap();
+            json(202, &job).with_header(location)
+        }
+        (Method::Get, ["jobs"]) => json(200, &*jobs.lock().unwrap()),
+        (Method::Get, ["jobs", id]) => {
+            let jobs = jobs.lock().unwrap();
+            match id.parse::<usize>().ok().and_then(|id| jobs.get(id)) {
+                Some(job) => json(200, job),
+                None => error(404, format!("no job {id}")),
+            }
+        }
+        (_, ["estimate"] | ["jobs"] | ["jobs", _]) => error(405, "method not allowed"),
+        _ => error(404, format!("no endpoint {path}")),
+    }
+}
+
+/// Serve the estimates until the process is killed.
+pub fn serve(args: &ServeArgs, cache: Option<Cache>, quiet: bool) -> Result<(), String> {
+    let server =
+        Server::http(&args.addr).map_err(|err| format!("cannot listen on {}: {err}", args.addr))?;
+    println!("Listening on http://{}", server.server_addr());
+
+    let jobs = Jobs::default();
+    let worker = spawn_worker(jobs.clone(), cache, quiet);
+    run(&server, &jobs, &worker, quiet);
+    Ok(())
+}
+
+/// Answer the requests of `server` until it is unblocked, sending the estimates to `worker`.
+fn run(server: &Server, jobs: &Jobs, worker: &Sender<usize>, quiet: bool) {
+    for mut request in server.incoming_requests() {
+        let response = handle(&mut request, jobs, worker);
+        if !quiet {
+            eprintln!(
+                "{} {} -> {}",
+                request.method(),
+                request.url(),
+                response.status_code().0
+            );
+        }
+        if let Err(err) = request.respond(response) {
+            eprintln!("Warning: cannot send the response: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::time::Duration;
+
+    /// Server on a free port of the loopback interface, answering with `worker`.
+    struct TestServer {
+        server: Arc<Server>,
+        addr: SocketAddr,
+    }
+
+    impl TestServer {
+        fn start(jobs: Jobs, worker: Sender<usize>) -> Self {
+            let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+            let addr = server.server_addr().to_ip().unwrap();
+            let running = server.clone();
+            thread::spawn(move || run(&running, &jobs, &worker, true));
+            TestServer { server, addr }
+        }
+
+        /// Status, headers and body of the response to `method path` with `body`.
+        fn request(&self, method: &str, path: &str, body: &str) -> (u16, String, String) {
+            let mut stream = TcpStream::connect(self.addr).unwrap();
+            write!(
+                stream,
+                "{method} {path} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\
+                 Content-Length: {}\r\n\r\n{body}",
+                self.addr,
+                body.len()
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+
+            let (head, body) = response.split_once("\r\n\r\n").unwrap();
+            let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+            (status, head.to_string(), body.to_string())
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            self.server.unblock();
+        }
+    }
+
+    #[test]
+    fn test_estimate() {
+        let jobs = Jobs::default();
+        let server = TestServer::start(jobs.clone(), spawn_worker(jobs, None, true));
+
+        let body = r#"{"p": 127, "t": 24, "w": 16, "variant": "original"}"#;
+        let (status, head, _) = server.request("POST", "/estimate", body);
+        assert_eq!(status, 202);
+        assert!(head.contains("Location: /jobs/0"), "{head}");
+
+        let job = loop {
+            let (status, _, body) = server.request("GET", "/jobs/0", "");
+            assert_eq!(status, 200);
+            let job: serde_json::Value = serde_json::from_str(&body).unwrap();
+            if job["status"] != "queued" && job["status"] != "running" {
+                break job;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(job["status"], "done");
+        let estimate: Estimate = serde_json::from_value(job["estimate"].clone()).unwrap();
+        let backend = Backend::most_precise().unwrap();
+        let expected = crate::estimate_cached(127, 24, 16, Variant::Original, backend, None);
+        assert_eq!((estimate.ts, estimate.cost), (expected.ts, expected.cost));
+
+        let (status, _, body) = server.request("GET", "/jobs", "");
+        assert_eq!(status, 200);
+        assert_eq!(
+            serde_json::from_str::<Vec<serde_json::Value>>(&body)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let jobs = Jobs::default();
+        let server = TestServer::start(jobs.clone(), spawn_worker(jobs, None, true));
+
+        for body in [
+            "not json",
+            r#"{"p": 127, "t": 24, "w": 16}"#,
+            r#"{"p": 127, "t": 24, "w": 25, "variant": "new"}"#,
+            r#"{"p": 127, "t": 24, "w": 16, "variant": "new", "extra": 1}"#,
+        ] {
+            assert_eq!(server.request("POST", "/estimate", body).0, 400, "{body}");
+        }
+        assert_eq!(server.request("GET", "/jobs/0", "").0, 404);
+        assert_eq!(server.request("GET", "/jobs/zero", "").0, 404);
+        assert_eq!(server.request("GET", "/nowhere", "").0, 404);
+        assert_eq!(server.request("GET", "/estimate", "").0, 405);
+        assert_eq!(server.request("DELETE", "/jobs/0", "").0, 405);
+    }
+
+    #[test]
+    fn test_worker_stopped() {
+        let (worker, receiver) = mpsc::channel();
+        drop(receiver);
+        let jobs = Jobs::default();
+        let server = TestServer::start(jobs.clone(), worker);
+
+        let body = r#"{"p": 127, "t": 24, "w": 16, "variant": "original"}"#;
+        assert_eq!(server.request("POST", "/estimate", body).0, 503);
+        assert_eq!(jobs.lock().unwrap()[0].status, Status::Failed);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::Params;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use revise_cross_parameters::estimate_attack_new;
+use revise_cross_parameters::float::Float;
+use revise_cross_parameters::toy::{self, forge, keygen};
+
+#[derive(clap::Args, Debug)]
+pub struct ToyArgs {
+    #[command(flatten)]
+    params: Params,
+
+    /// Generator of the restricted subgroup E of Fp^*
+    #[arg(short, default_value_t = 2)]
+    g: i64,
+
+    /// Code length
+    #[arg(short, default_value_t = 8)]
+    n: usize,
+
+    /// Code dimension
+    #[arg(short, default_value_t = 4)]
+    k: usize,
+
+    /// Number of forgeries to average over
+    #[arg(long, default_value_t = 1)]
+    runs: u64,
+
+    /// Seed of the random number generator
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+/// Forge signatures on a toy CROSS instance using the optimal parameters of our attack.
+pub fn toy<T: Float>(args: &ToyArgs, quiet: bool) -> Result<(), String> {
+    let Params { p, t, w } = args.params;
+    let params = toy::Params {
+        p,
+        g: args.g,
+        n: args.n,
+        k: args.k,
+        t,
+        w,
+    };
+    params.check()?;
+
+    println!("Estimating complexity of our attack...");
+    let (ts, aa, comp) = estimate_attack_new::<T>(t, w, p, quiet);
+    println!("Our attack has a predicted cost of {:.2} bits", comp);
+    println!("Our attack is optimized for t* = {} and alpha = {}", ts, aa);
+
+    println!();
+
+    let mut rng = StdRng::seed_from_u64(args.seed);
+    let (pk, _) = keygen(params, &mut rng);
+
+    println!(
+        "Forging {} signature(s) on a toy instance with n = {} and k = {}...",
+        args.runs, args.n, args.k
+    );
+    let mut total = 0;
+    for run in 1..=args.runs {
+        let forgery = forge(&pk, run, ts, aa, &mut rng);
+        let verifies = pk.verify(run, &forgery.signature);
+        println!(
+            "Forgery {}: {} attempts ({} first phase, {} second phase), signature {}",
+            run,
+            forgery.attempts(),
+            forgery.first_attempts,
+            forgery.second_attempts,
+            if verifies {
+                "verifies"
+            } else {
+                "DOES NOT verify"
+            }
+        );
+        total += forgery.attempts();
+    }
+
+    println!();
+    println!(
+        "Average cost of {:.2} bits against a prediction of {:.2} bits",
+        (total as f64 / args.runs as f64).log2(),
+        comp
+    );
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::Params;
+use revise_cross_parameters::verify::{check_consistency, Failure, Quantity};
+
+#[derive(clap::Args, Debug)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    params: Params,
+
+    /// Number of rounds t* at which the probabilities are compared (default the optimum of our attack)
+    #[arg(long)]
+    ts: Option<i64>,
+
+    /// Maximum accepted relative error
+    #[arg(long, default_value_t = 1e-6)]
+    tolerance: f64,
+}
+
+/// Compare every enabled backend against the exact values, and its optima against the most precise
+/// backend.
+///
+/// Returns [`true`] if all the backends are within the tolerance and agree on the optima.
+pub fn verify(args: &VerifyArgs, quiet: bool) -> bool {
+    let Params { p, t, w } = args.params;
+
+    println!("Evaluating every backend...");
+    let report = check_consistency(t, w, p, args.ts, args.tolerance, quiet);
+
+    println!();
+    println!(
+        "Relative errors against the exact values at t* = {}, optima against {} ({} bits)",
+        report.ts,
+        report.reference.backend,
+        report.reference.backend.precision()
+    );
+    print!("{:<8}", "Backend");
+    for quantity in Quantity::ALL {
+        print!(" {:>20}", quantity);
+    }
+    println!();
+    for evaluation in std::iter::once(&report.reference).chain(&report.evaluations) {
+        print!("{:<8}", evaluation.backend);
+        for quantity in Quantity::ALL {
+            print!(" {:>20.2e}", report.relative_error(evaluation, quantity));
+        }
+        println!();
+    }
+
+    println!();
+    let failures = report.failures();
+    if failures.is_empty() {
+        println!(
+            "All backends are within a relative error of {:.0e} and agree on the optima",
+            report.tolerance
+        );
+    }
+    for failure in &failures {
+        match failure {
+            Failure::Error {
+                backend,
+                quantity,
+                error,
+            } => println!(
+                "{} exceeds the tolerance of {:.0e} for {} with a relative error of {:.2e}",
+                backend, report.tolerance, quantity, error
+            ),
+            Failure::Optimum {
+                backend,
+                quantity,
+                optimum,
+                reference,
+            } => println!(
+                "{} finds {} for {} instead of {}",
+                backend, optimum, quantity, reference
+            ),
+        }
+    }
+    failures.is_empty()
+}
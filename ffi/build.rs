@@ -0,0 +1,14 @@
+use std::env;
+use std::path::Path;
+
+fn main() {
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    // the committed header in include/ is checked against this one by the tests
+    cbindgen::generate(&crate_dir)
+        .expect("cannot generate the C header")
+        .write_to_file(Path::new(&out_dir).join("revise_cross_parameters.h"));
+}
@@ -0,0 +1,92 @@
+#ifndef REVISE_CROSS_PARAMETERS_H
+#define REVISE_CROSS_PARAMETERS_H
+
+/* Generated by cbindgen from ffi/src/lib.rs, do not edit. */
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// The estimate succeeded.
+#define RCP_OK 0
+
+// The parameters are out of range.
+#define RCP_ERROR_INVALID_PARAMS 1
+
+// The backend id is unknown.
+#define RCP_ERROR_UNKNOWN_BACKEND 2
+
+// The backend was not enabled when building the library.
+#define RCP_ERROR_BACKEND_DISABLED 3
+
+// The estimator panicked.
+#define RCP_ERROR_PANIC 4
+
+// The most precise enabled backend.
+#define RCP_BACKEND_DEFAULT 0
+
+// `f64`, enabled by the **inexact** feature.
+#define RCP_BACKEND_F64 1
+
+// `f64` with an extended exponent, enabled by the **inexact** feature.
+#define RCP_BACKEND_EXT_F64 2
+
+// `rug::Float`, enabled by the **rug** feature.
+#define RCP_BACKEND_RUG 3
+
+// `dashu_float::FBig`, enabled by the **dashu** feature.
+#define RCP_BACKEND_DASHU 4
+
+// `f128`, enabled by the **nightly-float** feature.
+#define RCP_BACKEND_F128 5
+
+// Double-double, enabled by the **double-double** feature.
+#define RCP_BACKEND_DD 6
+
+// Optimum of an estimate.
+typedef struct RcpEstimate {
+  // `RCP_OK`, or one of the `RCP_ERROR_*` codes, in which case the other fields are unset.
+  int32_t error;
+  // Optimal number of rounds `t*` guessed in the first phase.
+  int64_t ts;
+  // Optimal number `alpha` of 1s guessed in the second challenge, -1 for the original attack.
+  int64_t alpha;
+  // Cost as log2 of the estimated gate count.
+  double cost;
+} RcpEstimate;
+
+// Parameters of a CROSS instance and backend of an estimate.
+typedef struct RcpParams {
+  // Prime order of finite field Fp.
+  int64_t p;
+  // Number of parallel repetitions.
+  int64_t t;
+  // Fixed-weight parameter for the second challenge.
+  int64_t w;
+  // One of the `RCP_BACKEND_*` ids.
+  uint32_t backend;
+} RcpParams;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Estimate the cost of the original attack on CROSS.
+struct RcpEstimate rcp_estimate_attack(struct RcpParams params);
+
+// Estimate the cost of our attack on CROSS.
+struct RcpEstimate rcp_estimate_attack_new(struct RcpParams params);
+
+// Returns `true` if the backend `id` is enabled.
+bool rcp_backend_enabled(uint32_t id);
+
+// Number of significant bits of the backend `id`, or 0 if it is not enabled.
+uint32_t rcp_backend_precision(uint32_t id);
+
+// Version of the library, as a static NUL-terminated string.
+const char *rcp_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* REVISE_CROSS_PARAMETERS_H */
@@ -0,0 +1,165 @@
+//! C ABI for the estimators of the `revise_cross_parameters` crate.
+//!
+//! The functions take and return plain C structs by value, so that they can be called from C and
+//! C++ with the header `include/revise_cross_parameters.h` generated by cbindgen, and from Python with
+//! `ctypes`. Panics never cross the boundary: they are reported as `RCP_ERROR_PANIC`.
+
+use revise_cross_parameters::float::Backend;
+use revise_cross_parameters::{
+    check_params, estimate_attack, estimate_attack_new, with_backend, VERSION,
+};
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::OnceLock;
+
+/// The estimate succeeded.
+pub const RCP_OK: i32 = 0;
+/// The parameters are out of range.
+pub const RCP_ERROR_INVALID_PARAMS: i32 = 1;
+/// The backend id is unknown.
+pub const RCP_ERROR_UNKNOWN_BACKEND: i32 = 2;
+/// The backend was not enabled when building the library.
+pub const RCP_ERROR_BACKEND_DISABLED: i32 = 3;
+/// The estimator panicked.
+pub const RCP_ERROR_PANIC: i32 = 4;
+
+/// The most precise enabled backend.
+pub const RCP_BACKEND_DEFAULT: u32 = 0;
+/// `f64`, enabled by the **inexact** feature.
+pub const RCP_BACKEND_F64: u32 = 1;
+/// `f64` with an extended exponent, enabled by the **inexact** feature.
+pub const RCP_BACKEND_EXT_F64: u32 = 2;
+/// `rug::Float`, enabled by the **rug** feature.
+pub const RCP_BACKEND_RUG: u32 = 3;
+/// `dashu_float::FBig`, enabled by the **dashu** feature.
+pub const RCP_BACKEND_DASHU: u32 = 4;
+/// `f128`, enabled by the **nightly-float** feature.
+pub const RCP_BACKEND_F128: u32 = 5;
+/// Double-double, enabled by the **double-double** feature.
+pub const RCP_BACKEND_DD: u32 = 6;
+
+/// Parameters of a CROSS instance and backend of an estimate.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RcpParams {
+    /// Prime order of finite field Fp.
+    pub p: i64,
+    /// Number of parallel repetitions.
+    pub t: i64,
+    /// Fixed-weight parameter for the second challenge.
+    pub w: i64,
+    /// One of the `RCP_BACKEND_*` ids.
+    pub backend: u32,
+}
+
+/// Optimum of an estimate.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RcpEstimate {
+    /// `RCP_OK`, or one of the `RCP_ERROR_*` codes, in which case the other fields are unset.
+    pub error: i32,
+    /// Optimal number of rounds `t*` guessed in the first phase.
+    pub ts: i64,
+    /// Optimal number `alpha` of 1s guessed in the second challenge, -1 for the original attack.
+    pub alpha: i64,
+    /// Cost as log2 of the estimated gate count.
+    pub cost: f64,
+}
+
+impl RcpEstimate {
+    fn error(error: i32) -> Self {
+        RcpEstimate {
+            error,
+            ts: -1,
+            alpha: -1,
+            cost: f64::NAN,
+        }
+    }
+}
+
+/// Backend of `id`, or the error code.
+fn backend(id: u32) -> Result<Backend, i32> {
+    let backend = match id {
+        RCP_BACKEND_DEFAULT => Backend::most_precise(),
+        #[cfg(feature = "inexact")]
+        RCP_BACKEND_F64 => Some(Backend::F64),
+        #[cfg(feature = "inexact")]
+        RCP_BACKEND_EXT_F64 => Some(Backend::ExtF64),
+        #[cfg(feature = "rug")]
+        RCP_BACKEND_RUG => Some(Backend::Rug),
+        #[cfg(feature = "dashu")]
+        RCP_BACKEND_DASHU => Some(Backend::Dashu),
+        #[cfg(feature = "nightly-float")]
+        RCP_BACKEND_F128 => Some(Backend::F128),
+        #[cfg(feature = "double-double")]
+        RCP_BACKEND_DD => Some(Backend::DoubleDouble),
+        RCP_BACKEND_F64..=RCP_BACKEND_DD => None,
+        _ => return Err(RCP_ERROR_UNKNOWN_BACKEND),
+    };
+    backend.ok_or(RCP_ERROR_BACKEND_DISABLED)
+}
+
+/// Check the parameters and run `estimate` with their backend, catching its panics.
+fn run(params: RcpParams, estimate: impl FnOnce(Backend) -> RcpEstimate) -> RcpEstimate {
+    let RcpParams { p, t, w, backend } = params;
+    if check_params(t, w, p).is_err() {
+        return RcpEstimate::error(RCP_ERROR_INVALID_PARAMS);
+    }
+    match self::backend(backend) {
+        Ok(backend) => catch_unwind(AssertUnwindSafe(|| estimate(backend)))
+            .unwrap_or_else(|_| RcpEstimate::error(RCP_ERROR_PANIC)),
+        Err(error) => RcpEstimate::error(error),
+    }
+}
+
+/// Estimate the cost of the original attack on CROSS.
+#[no_mangle]
+pub extern "C" fn rcp_estimate_attack(params: RcpParams) -> RcpEstimate {
+    run(params, |backend| {
+        let RcpParams { p, t, w, .. } = params;
+        let (ts, cost) = with_backend!(backend, T => estimate_attack::<T>(t, w, p, true));
+        RcpEstimate {
+            error: RCP_OK,
+            ts,
+            alpha: -1,
+            cost,
+        }
+    })
+}
+
+/// Estimate the cost of our attack on CROSS.
+#[no_mangle]
+pub extern "C" fn rcp_estimate_attack_new(params: RcpParams) -> RcpEstimate {
+    run(params, |backend| {
+        let RcpParams { p, t, w, .. } = params;
+        let (ts, alpha, cost) =
+            with_backend!(backend, T => estimate_attack_new::<T>(t, w, p, true));
+        RcpEstimate {
+            error: RCP_OK,
+            ts,
+            alpha,
+            cost,
+        }
+    })
+}
+
+/// Returns `true` if the backend `id` is enabled.
+#[no_mangle]
+pub extern "C" fn rcp_backend_enabled(id: u32) -> bool {
+    backend(id).is_ok()
+}
+
+/// Number of significant bits of the backend `id`, or 0 if it is not enabled.
+#[no_mangle]
+pub extern "C" fn rcp_backend_precision(id: u32) -> u32 {
+    backend(id).map_or(0, |backend| backend.precision())
+}
+
+/// Version of the library, as a static NUL-terminated string.
+#[no_mangle]
+pub extern "C" fn rcp_version() -> *const c_char {
+    static VERSION_C: OnceLock<CString> = OnceLock::new();
+    VERSION_C
+        .get_or_init(|| CString::new(VERSION).unwrap())
+        .as_ptr()
+}
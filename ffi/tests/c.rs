@@ -0,0 +1,52 @@
+//! Build `estimate.c` against the library and the committed header, and run it.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn test_c_program() {
+    // target/<profile>/deps/<test> -> target/<profile>, where the library is built
+    let exe = env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap().parent().unwrap();
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let program = lib_dir.join("estimate_c");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .arg(crate_dir.join("tests/estimate.c"))
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg("-L")
+        .arg(lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .args([
+            "-lrevise_cross_parameters_ffi",
+            "-lm",
+            "-Wall",
+            "-Werror",
+            "-o",
+        ])
+        .arg(&program)
+        .status()
+        .expect("cannot run the C compiler");
+    assert!(status.success(), "cannot compile estimate.c");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_header() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/revise_cross_parameters.h"));
+    let committed = include_str!("../include/revise_cross_parameters.h");
+    assert!(
+        generated == committed,
+        "include/revise_cross_parameters.h is outdated, replace it with {}/revise_cross_parameters.h",
+        env!("OUT_DIR")
+    );
+}
@@ -0,0 +1,57 @@
+/* Estimate a small CROSS instance through the C ABI, checking the results and the error codes. */
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "revise_cross_parameters.h"
+
+#define CHECK(cond)                                                              \
+    do {                                                                         \
+        if (!(cond)) {                                                           \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            return 1;                                                            \
+        }                                                                        \
+    } while (0)
+
+int main(void) {
+    CHECK(strlen(rcp_version()) > 0);
+    CHECK(rcp_backend_enabled(RCP_BACKEND_DEFAULT));
+    CHECK(rcp_backend_precision(RCP_BACKEND_DEFAULT) >= 32);
+    CHECK(!rcp_backend_enabled(99));
+    CHECK(rcp_backend_precision(99) == 0);
+
+    RcpParams params = {.p = 13, .t = 24, .w = 18, .backend = RCP_BACKEND_DEFAULT};
+
+    RcpEstimate original = rcp_estimate_attack(params);
+    CHECK(original.error == RCP_OK);
+    CHECK(original.ts == 8 && original.alpha == -1);
+    CHECK(fabs(original.cost - 12.51) < 0.005);
+
+    RcpEstimate ours = rcp_estimate_attack_new(params);
+    CHECK(ours.error == RCP_OK);
+    CHECK(ours.ts == 7 && ours.alpha == 21);
+    CHECK(fabs(ours.cost - 11.88) < 0.005);
+
+    RcpParams invalid = {.p = 13, .t = 24, .w = 25, .backend = RCP_BACKEND_DEFAULT};
+    CHECK(rcp_estimate_attack(invalid).error == RCP_ERROR_INVALID_PARAMS);
+
+    RcpParams unknown = {.p = 13, .t = 24, .w = 18, .backend = 99};
+    RcpEstimate error = rcp_estimate_attack_new(unknown);
+    CHECK(error.error == RCP_ERROR_UNKNOWN_BACKEND && isnan(error.cost));
+
+    for (uint32_t id = RCP_BACKEND_F64; id <= RCP_BACKEND_DD; id++) {
+        RcpParams backend = {.p = 13, .t = 24, .w = 18, .backend = id};
+        RcpEstimate estimate = rcp_estimate_attack(backend);
+        if (rcp_backend_enabled(id)) {
+            CHECK(estimate.error == RCP_OK && estimate.ts == original.ts);
+        } else {
+            CHECK(estimate.error == RCP_ERROR_BACKEND_DISABLED);
+        }
+    }
+
+    printf("rcp %s: original %.2f bits (t* = %lld), ours %.2f bits (t* = %lld, alpha = %lld)\n",
+           rcp_version(), original.cost, (long long)original.ts, ours.cost, (long long)ours.ts,
+           (long long)ours.alpha);
+    return 0;
+}
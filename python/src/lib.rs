@@ -0,0 +1,246 @@
+//! Python bindings for the estimators of the `revise_cross_parameters` crate.
+//!
+//! The module is built with [maturin](https://www.maturin.rs) and imported as
+//! `revise_cross_parameters`. The probabilities are returned as their log2, as they underflow
+//! Python floats for large `t`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use revise_cross_parameters::float::{Backend, Float};
+use revise_cross_parameters::{
+    check_params, estimate_attack, estimate_attack_new, prob_b, prob_b_new, prob_b_new_alpha,
+    prob_beta, with_backend, VERSION,
+};
+
+/// Backend named `name`, or the most precise one if `None`.
+fn backend(name: Option<&str>) -> PyResult<Backend> {
+    let Some(name) = name else {
+        return Backend::most_precise().ok_or_else(|| PyValueError::new_err("no backend enabled"));
+    };
+    Backend::ENABLED
+        .iter()
+        .copied()
+        .find(|backend| backend.name() == name)
+        .ok_or_else(|| {
+            let names: Vec<_> = Backend::ENABLED.iter().map(Backend::name).collect();
+            PyValueError::new_err(format!(
+                "unknown backend {name:?}, expected one of {}",
+                names.join(", ")
+            ))
+        })
+}
+
+/// Check the CROSS parameters, which would otherwise panic in the estimators.
+fn check(t: i64, w: i64, p: i64) -> PyResult<()> {
+    check_params(t, w, p).map_err(PyValueError::new_err)
+}
+
+/// Check that `value` named `name` is a number of rounds between 0 and `t`.
+fn check_rounds(name: &str, value: i64, t: i64) -> PyResult<()> {
+    if (0..=t).contains(&value) {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "{name} must be between 0 and t"
+        )))
+    }
+}
+
+/// Optimum of an estimate.
+#[pyclass(
+    name = "Estimate",
+    module = "revise_cross_parameters",
+    frozen,
+    get_all,
+    eq
+)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PyEstimate {
+    /// Optimal number of rounds t* guessed in the first phase.
+    ts: i64,
+    /// Optimal number alpha of 1s guessed in the second challenge, None for the original attack.
+    alpha: Option<i64>,
+    /// Cost as log2 of the estimated gate count.
+    cost: f64,
+}
+
+#[pymethods]
+impl PyEstimate {
+    fn __repr__(&self) -> String {
+        match self.alpha {
+            Some(alpha) => format!(
+                "Estimate(ts={}, alpha={}, cost={})",
+                self.ts, alpha, self.cost
+            ),
+            None => format!("Estimate(ts={}, alpha=None, cost={})", self.ts, self.cost),
+        }
+    }
+}
+
+/// Estimate the cost of the original attack on CROSS.
+///
+/// Returns the optimal t* and the cost as log2 of the estimated gate count. The backend is the
+/// name of one of BACKENDS, by default DEFAULT_BACKEND.
+#[pyfunction(name = "estimate_attack")]
+#[pyo3(signature = (t, w, p, backend = None, quiet = true))]
+fn py_estimate_attack(
+    py: Python<'_>,
+    t: i64,
+    w: i64,
+    p: i64,
+    backend: Option<&str>,
+    quiet: bool,
+) -> PyResult<PyEstimate> {
+    let backend = self::backend(backend)?;
+    check(t, w, p)?;
+
+    let (ts, cost) =
+        py.allow_threads(|| with_backend!(backend, T => estimate_attack::<T>(t, w, p, quiet)));
+    Ok(PyEstimate {
+        ts,
+        alpha: None,
+        cost,
+    })
+}
+
+/// Estimate the cost of our attack on CROSS.
+///
+/// Returns the optimal t* and alpha and the cost as log2 of the estimated gate count. The backend
+/// is the name of one of BACKENDS, by default DEFAULT_BACKEND.
+#[pyfunction(name = "estimate_attack_new")]
+#[pyo3(signature = (t, w, p, backend = None, quiet = true))]
+fn py_estimate_attack_new(
+    py: Python<'_>,
+    t: i64,
+    w: i64,
+    p: i64,
+    backend: Option<&str>,
+    quiet: bool,
+) -> PyResult<PyEstimate> {
+    let backend = self::backend(backend)?;
+    check(t, w, p)?;
+
+    let (ts, alpha, cost) =
+        py.allow_threads(|| with_backend!(backend, T => estimate_attack_new::<T>(t, w, p, quiet)));
+    Ok(PyEstimate {
+        ts,
+        alpha: Some(alpha),
+        cost,
+    })
+}
+
+/// log2 of the success probability of the first phase, guessing at least ts first challenges.
+#[pyfunction(name = "prob_beta")]
+#[pyo3(signature = (t, ts, p, backend = None))]
+fn py_prob_beta(t: i64, ts: i64, p: i64, backend: Option<&str>) -> PyResult<f64> {
+    let backend = self::backend(backend)?;
+    check(t, 0, p)?;
+    check_rounds("ts", ts, t)?;
+
+    Ok(with_backend!(backend, T => prob_beta::<T>(t, ts, p).log2()))
+}
+
+/// log2 of the success probability of the second phase of the original attack.
+#[pyfunction(name = "prob_b")]
+#[pyo3(signature = (t, ts, w, p, backend = None))]
+fn py_prob_b(t: i64, ts: i64, w: i64, p: i64, backend: Option<&str>) -> PyResult<f64> {
+    let backend = self::backend(backend)?;
+    check(t, w, p)?;
+    check_rounds("ts", ts, t)?;
+
+    Ok(with_backend!(backend, T => prob_b::<T>(t, ts, w, p).log2()))
+}
+
+/// Optimal alpha and log2 of the success probability of the second phase of our attack.
+#[pyfunction(name = "prob_b_new")]
+#[pyo3(signature = (t, ts, w, p, backend = None))]
+fn py_prob_b_new(t: i64, ts: i64, w: i64, p: i64, backend: Option<&str>) -> PyResult<(i64, f64)> {
+    let backend = self::backend(backend)?;
+    check(t, w, p)?;
+    check_rounds("ts", ts, t)?;
+
+    Ok(with_backend!(backend, T => {
+        let (alpha, prob) = prob_b_new::<T>(t, ts, w, p);
+        (alpha, prob.log2())
+    }))
+}
+
+/// log2 of the success probability of the second phase of our attack, guessing alpha 1s.
+#[pyfunction(name = "prob_b_new_alpha")]
+#[pyo3(signature = (t, ts, w, p, alpha, backend = None))]
+fn py_prob_b_new_alpha(
+    t: i64,
+    ts: i64,
+    w: i64,
+    p: i64,
+    alpha: i64,
+    backend: Option<&str>,
+) -> PyResult<f64> {
+    let backend = self::backend(backend)?;
+    check(t, w, p)?;
+    check_rounds("ts", ts, t)?;
+    check_rounds("alpha", alpha, t)?;
+
+    Ok(with_backend!(backend, T => prob_b_new_alpha::<T>(t, ts, w, p, alpha).log2()))
+}
+
+/// Estimates of a forgery attack on CROSS involving the fixed-weight distribution of the second
+/// challenge.
+#[pymodule]
+#[pyo3(name = "revise_cross_parameters")]
+fn py_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let backends: Vec<_> = Backend::ENABLED.iter().map(Backend::name).collect();
+    m.add("__version__", VERSION)?;
+    m.add("BACKENDS", backends)?;
+    m.add("DEFAULT_BACKEND", backend(None)?.name())?;
+
+    m.add_class::<PyEstimate>()?;
+    m.add_function(wrap_pyfunction!(py_estimate_attack, m)?)?;
+    m.add_function(wrap_pyfunction!(py_estimate_attack_new, m)?)?;
+    m.add_function(wrap_pyfunction!(py_prob_beta, m)?)?;
+    m.add_function(wrap_pyfunction!(py_prob_b, m)?)?;
+    m.add_function(wrap_pyfunction!(py_prob_b_new, m)?)?;
+    m.add_function(wrap_pyfunction!(py_prob_b_new_alpha, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimates() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for &backend in Backend::ENABLED {
+                let name = Some(backend.name());
+                let (ts, cost) =
+                    with_backend!(backend, T => estimate_attack::<T>(24, 16, 127, true));
+                assert_eq!(
+                    py_estimate_attack(py, 24, 16, 127, name, true).unwrap(),
+                    PyEstimate {
+                        ts,
+                        alpha: None,
+                        cost
+                    }
+                );
+
+                let (ts, alpha, cost) =
+                    with_backend!(backend, T => estimate_attack_new::<T>(24, 16, 127, true));
+                assert_eq!(
+                    py_estimate_attack_new(py, 24, 16, 127, name, true).unwrap(),
+                    PyEstimate {
+                        ts,
+                        alpha: Some(alpha),
+                        cost
+                    }
+                );
+            }
+
+            let err = py_estimate_attack(py, 24, 25, 127, None, true).unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(py_estimate_attack_new(py, 24, 16, 2, None, true).is_err());
+            assert!(py_estimate_attack(py, 24, 16, 127, Some("none"), true).is_err());
+        });
+    }
+}
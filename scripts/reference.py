@@ -0,0 +1,142 @@
+"""Reference values of the estimators, computed with the Decimal implementation of attack.ipynb.
+
+The functions below are copied from the notebook without changes (except for the progress bars),
+so that they remain an independent implementation of the formulas. Running the script prints the
+probabilities for every t* and the optimum of both attacks on a grid of small parameters as JSON,
+which tests/reference.rs compares against the Rust implementation:
+
+    python3 scripts/reference.py > tests/data/reference.json
+"""
+
+import json
+from decimal import Decimal
+from math import comb
+
+
+def binom(n, k):
+    if k < 0 or k > n:
+        return 0
+    else:
+        return comb(n, k)
+
+
+def prob_beta(t, ts, p):
+    return sum(
+        binom(t, j) * (1 / Decimal(p - 1)) ** j * (1 - 1 / Decimal(p - 1)) ** (t - j)
+        for j in range(ts, t + 1)
+    )
+
+
+def prob_b(t, ts, w, p):
+    return sum(
+        binom(t, j)
+        * (1 / Decimal(p - 1)) ** j
+        * (1 - 1 / Decimal(p - 1)) ** (t - j)
+        / prob_beta(t, ts, p)
+        * sum(
+            binom(j, ws) ** 2 * binom(t - j, w - ws) / Decimal(binom(t, w)) ** 2
+            for ws in range(max(0, j - (t - w)), min(j, w) + 1)
+        )
+        for j in range(ts, t + 1)
+    )
+
+
+def attack(t, w, p, verbose=False):
+    ts, comp = min(
+        [(ts, 1 / prob_beta(t, ts, p) + 1 / prob_b(t, ts, w, p)) for ts in range(t + 1)],
+        key=lambda x: x[1],
+    )
+    complog = comp.log10() / Decimal(2).log10()
+
+    if verbose:
+        return ts, complog
+    else:
+        return complog
+
+
+def prob_card_S(t, ts, j, p):
+    return (
+        binom(t, j)
+        * (1 / Decimal(p - 1)) ** j
+        * (1 - 1 / Decimal(p - 1)) ** (t - j)
+        / prob_beta(t, ts, p)
+    )
+
+
+def prob_b_new(t, ts, w, p, verbose=False):
+    aa, prob = max(
+        [
+            (
+                aa,
+                1
+                / (Decimal(binom(t, aa)) * Decimal(binom(t, w)))
+                * sum(
+                    prob_card_S(t, ts, j, p)
+                    * sum(
+                        binom(t - j, ws) * binom(j, aa - ws) * binom(j, w - ws)
+                        for ws in range(max(0, aa - j), min(t - j, aa) + 1)
+                    )
+                    for j in range(ts, t + 1)
+                ),
+            )
+            for aa in range(w, t + 1)
+        ],
+        key=lambda x: x[1],
+    )
+
+    if verbose:
+        return aa, prob
+    else:
+        return prob
+
+
+def attack_new(t, w, p, verbose=False):
+    ts, comp = min(
+        [(ts, 1 / prob_beta(t, ts, p) + 1 / prob_b_new(t, ts, w, p)) for ts in range(t + 1)],
+        key=lambda x: x[1],
+    )
+    complog = comp.log10() / Decimal(2).log10()
+
+    if verbose:
+        aa, _ = prob_b_new(t, ts, w, p, verbose=True)
+        return ts, aa, complog
+    else:
+        return complog
+
+
+def log2(x):
+    return float(x.ln() / Decimal(2).ln())
+
+
+def case(t, w, p):
+    """Probabilities for every t* and optima of both attacks, as log2."""
+    ts, cost = attack(t, w, p, verbose=True)
+    ts_new, aa, cost_new = attack_new(t, w, p, verbose=True)
+    return {
+        "p": p,
+        "t": t,
+        "w": w,
+        "prob_beta": [log2(prob_beta(t, ts, p)) for ts in range(t + 1)],
+        "prob_b": [log2(prob_b(t, ts, w, p)) for ts in range(t + 1)],
+        "prob_b_new": [
+            [aa, log2(prob)]
+            for aa, prob in (prob_b_new(t, ts, w, p, verbose=True) for ts in range(t + 1))
+        ],
+        "attack": {"ts": ts, "cost": float(cost)},
+        "attack_new": {"ts": ts_new, "alpha": aa, "cost": float(cost_new)},
+    }
+
+
+def main():
+    cases = [
+        case(t, w, p)
+        for p in (3, 5, 13, 127, 509)
+        for t in (1, 4, 9, 16, 24)
+        for w in sorted({0, t // 2, 3 * t // 4, t})
+    ]
+    # one case per line
+    print('{"cases": [\n' + ",\n".join(json.dumps(case) for case in cases) + "\n]}")
+
+
+if __name__ == "__main__":
+    main()
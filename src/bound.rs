@@ -0,0 +1,425 @@
+//! Rounding error bounds of the probabilities and costs, computed along with them.
+//!
+//! [`Bounded`] wraps a [`Float`] backend and keeps, next to each value, a bound on its relative
+//! error to first order in the unit roundoff (running error analysis). Evaluating the probabilities
+//! of [`scheme`] or [`composition`](crate::composition) with `Bounded<T>` instead of
+//! `T` performs exactly the same operations on the same values, so that the bound follows the code
+//! instead of a separate count of its operations. The combinatorial sums are exact (see
+//! [`Float::from_bigint`]), and only add the rounding of their conversion.
+//!
+//! Overflow and underflow are not accounted for: with the [`F64Num`](crate::float::F64Num) backend
+//! and large `t`, the bounds are meaningless.
+
+use crate::float::{Backend, BigInt, Float, FloatAssignOps, FloatOps, MulDivAssign};
+use crate::scheme::{self, Scheme};
+use num_traits::{One, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+/// Bound on the relative error of a single operation of `backend`.
+///
+/// This is twice the unit roundoff of rounding to the nearest, to cover the backends that
+/// truncate (`dashu`), except for `dd` whose operations are not correctly rounded: the product of
+/// two double-word numbers has a relative error of at most `7u^2`, where `u = 2^-53`, and the
+/// division is less accurate (Joldes, Muller and Popescu, *Tight and rigorous error bounds for
+/// basic building blocks of double-word arithmetic*, 2017), so `16u^2` is used.
+pub fn unit_roundoff(backend: Backend) -> f64 {
+    #[cfg(feature = "double-double")]
+    if matches!(backend, Backend::DoubleDouble) {
+        return 2f64.powi(4 - 2 * f64::MANTISSA_DIGITS as i32);
+    }
+    2f64.powi(2 - backend.precision() as i32)
+}
+
+/// Value of the backend `T` together with a bound on its relative error.
+///
+/// The operations are the ones of `T`, with the error bounds of first order:
+///
+/// * the integers that fit in the precision of `T` are exact, as are their sums, differences and
+///   products as long as they fit;
+/// * a product or quotient adds one rounding to the errors of its operands, and
+///   [`mul_div_assign`](MulDivAssign::mul_div_assign) two;
+/// * a sum of two values of the same sign adds one rounding to the largest error of its operands,
+///   while with a cancellation the errors are scaled by the magnitude of the operands relative to
+///   the result;
+/// * a power of exponent `k` multiplies the error of its base by `k` and adds `k - 1` roundings,
+///   as a chain of products.
+#[derive(Debug)]
+pub struct Bounded<T> {
+    value: T,
+    /// Bound on the relative error of `value`, in units of [`unit_roundoff`].
+    error: f64,
+    /// `value` if it is an integer known exactly.
+    integer: Option<i64>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// log2 of the absolute value of `value`.
+fn log2_abs<T: Float>(value: &T) -> f64 {
+    if *value < T::zero() {
+        (T::zero() - value).log2()
+    } else {
+        value.log2()
+    }
+}
+
+impl<T: Float> Bounded<T> {
+    /// Value computed by the backend.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Bound on the relative error of the value.
+    pub fn relative_error(&self) -> f64 {
+        self.error * unit_roundoff(T::BACKEND)
+    }
+
+    /// Returns [`true`] if the integer `n` is represented exactly by `T`.
+    fn fits(n: i64) -> bool {
+        n.unsigned_abs() < 1 << T::BACKEND.precision().min(63)
+    }
+
+    fn exact(value: T, integer: i64) -> Self {
+        Bounded {
+            value,
+            error: 0.0,
+            integer: Some(integer),
+        }
+    }
+
+    fn rounded(value: T, error: f64) -> Self {
+        Bounded {
+            value,
+            error,
+            integer: None,
+        }
+    }
+
+    fn combine(self, rhs: &Self, op: Op) -> Self {
+        let integer = self
+            .integer
+            .zip(rhs.integer)
+            .and_then(|(a, b)| match op {
+                Op::Add => a.checked_add(b),
+                Op::Sub => a.checked_sub(b),
+                Op::Mul => a.checked_mul(b),
+                Op::Div => None,
+            })
+            .filter(|&n| Self::fits(n));
+        let (lhs_integer, lhs_error) = (self.integer, self.error);
+
+        match op {
+            Op::Add | Op::Sub => {
+                let zero = T::zero();
+                let cancellation = ((self.value < zero) != (rhs.value < zero)) == (op == Op::Add);
+                let lhs_log2 = cancellation.then(|| log2_abs(&self.value));
+                let value = match op {
+                    Op::Add => self.value + &rhs.value,
+                    _ => self.value - &rhs.value,
+                };
+
+                if let Some(integer) = integer {
+                    Self::exact(value, integer)
+                } else if lhs_integer == Some(0) {
+                    Self::rounded(value, rhs.error)
+                } else if rhs.integer == Some(0) {
+                    Self::rounded(value, lhs_error)
+                } else if let Some(lhs_log2) = lhs_log2 {
+                    let log2 = log2_abs(&value);
+                    let scaled = |error: f64, operand_log2: f64| {
+                        if error == 0.0 {
+                            0.0
+                        } else {
+                            error * (operand_log2 - log2).exp2()
+                        }
+                    };
+                    let error =
+                        scaled(lhs_error, lhs_log2) + scaled(rhs.error, log2_abs(&rhs.value));
+                    Self::rounded(value, error + 1.0)
+                } else {
+                    Self::rounded(value, lhs_error.max(rhs.error) + 1.0)
+                }
+            }
+            Op::Mul | Op::Div => {
+                let value = match op {
+                    Op::Mul => self.value * &rhs.value,
+                    _ => self.value / &rhs.value,
+                };
+
+                match (integer, lhs_integer, rhs.integer) {
+                    (Some(integer), _, _) => Self::exact(value, integer),
+                    (_, Some(1), _) if op == Op::Mul => Self::rounded(value, rhs.error),
+                    (_, _, Some(1)) => Self::rounded(value, lhs_error),
+                    _ => Self::rounded(value, lhs_error + rhs.error + 1.0),
+                }
+            }
+        }
+    }
+}
+
+/// Bound on the absolute error in bits of the log2 of `cost`, e.g. of
+/// [`attack_cost`](scheme::attack_cost) evaluated with [`Bounded`].
+///
+/// The final rounding of the logarithm to [`f64`] is not included.
+pub fn bits<T: Float>(cost: &Bounded<T>) -> f64 {
+    let relative = cost.relative_error();
+    if relative < 1.0 {
+        -(-relative).ln_1p() / std::f64::consts::LN_2
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Bound on the error in bits of the cost of the original attack on `scheme` guessing `ts` first
+/// challenges, as computed by [`scheme::attack_cost`] with `T`.
+pub fn attack_cost<T: Float>(scheme: &Scheme, ts: i64) -> f64 {
+    bits(&scheme::attack_cost::<Bounded<T>>(scheme, ts))
+}
+
+/// Bound on the error in bits of the cost of the new attack on `scheme` guessing `ts` first
+/// challenges, as computed by [`scheme::attack_new_cost`] with `T`.
+pub fn attack_new_cost<T: Float>(scheme: &Scheme, ts: i64) -> f64 {
+    bits(&scheme::attack_new_cost::<Bounded<T>>(scheme, ts).1)
+}
+
+macro_rules! bounded_ops {
+    ($($op:ident::$method:ident, $assign:ident::$assign_method:ident => $variant:ident;)*) => {
+        $(
+            impl<T: Float> $op for Bounded<T> {
+                type Output = Self;
+
+                #[inline]
+                fn $method(self, rhs: Self) -> Self {
+                    self.combine(&rhs, Op::$variant)
+                }
+            }
+
+            impl<'a, T: Float> $op<&'a Bounded<T>> for Bounded<T> {
+                type Output = Self;
+
+                #[inline]
+                fn $method(self, rhs: &'a Self) -> Self {
+                    self.combine(rhs, Op::$variant)
+                }
+            }
+
+            impl<'a, T: Float> $assign<&'a Bounded<T>> for Bounded<T> {
+                #[inline]
+                fn $assign_method(&mut self, rhs: &'a Self) {
+                    let lhs = std::mem::replace(self, Self::zero());
+                    *self = lhs.combine(rhs, Op::$variant);
+                }
+            }
+        )*
+    };
+}
+
+bounded_ops! {
+    Add::add, AddAssign::add_assign => Add;
+    Sub::sub, SubAssign::sub_assign => Sub;
+    Mul::mul, MulAssign::mul_assign => Mul;
+    Div::div, DivAssign::div_assign => Div;
+}
+
+impl<T: Float> FloatOps for Bounded<T> {}
+impl<T: Float> FloatOps<&Self, Self> for Bounded<T> {}
+impl<T: Float> FloatAssignOps<&Self> for Bounded<T> {}
+
+impl<'a, T: Float> MulDivAssign<&'a Self, &'a Self> for Bounded<T> {
+    fn mul_div_assign(&mut self, mul: &'a Self, div: &'a Self) {
+        self.value.mul_div_assign(&mul.value, &div.value);
+        self.error += mul.error + div.error + 2.0;
+        self.integer = None;
+    }
+}
+
+impl<T: Float> PartialEq for Bounded<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Float> PartialOrd for Bounded<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Float> Zero for Bounded<T> {
+    fn zero() -> Self {
+        Self::exact(T::zero(), 0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == T::zero()
+    }
+}
+
+impl<T: Float> One for Bounded<T> {
+    fn one() -> Self {
+        Self::exact(T::one(), 1)
+    }
+}
+
+impl<T: Float> Sum for Bounded<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |a, b| a + b)
+    }
+}
+
+impl<T: Float> From<i64> for Bounded<T> {
+    fn from(value: i64) -> Self {
+        if Self::fits(value) {
+            Self::exact(T::from(value), value)
+        } else {
+            Self::rounded(T::from(value), 1.0)
+        }
+    }
+}
+
+impl<T: Float> Float for Bounded<T> {
+    const BACKEND: crate::float::Backend = T::BACKEND;
+
+    fn pow(&self, exp: u32) -> Self {
+        let value = self.value.pow(exp);
+        match self.integer.and_then(|n| n.checked_pow(exp)) {
+            Some(integer) if Self::fits(integer) => Self::exact(value, integer),
+            _ if exp == 0 => Self::rounded(value, 0.0),
+            _ => Self::rounded(value, exp as f64 * self.error + (exp - 1) as f64),
+        }
+    }
+
+    fn log2(&self) -> f64 {
+        self.value.log2()
+    }
+
+    fn is_nan(&self) -> bool {
+        self.value.is_nan()
+    }
+
+    fn from_bigint(value: &BigInt) -> Self {
+        let converted = T::from_bigint(value);
+        match value.to_i64() {
+            Some(integer) if Self::fits(integer) => Self::exact(converted, integer),
+            _ if value.bits() <= T::BACKEND.precision() as u64 => Self::rounded(converted, 0.0),
+            _ => Self::rounded(converted, 1.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bounded;
+    use crate::float::{BigInt, Float};
+    use crate::verify::{ratio, relative_error};
+
+    /// Sets of `(p, t, w)` and `t*` at which the bounds are checked.
+    const PARAMS: [((i64, i64, i64), i64); 6] = [
+        ((13, 24, 18), 0),
+        ((13, 24, 18), 5),
+        ((3, 40, 20), 10),
+        ((127, 64, 40), 12),
+        ((7, 100, 60), 30),
+        ((127, 126, 93), 20),
+    ];
+
+    /// Asserts that the relative error of `value` with respect to `exact` is within its bound, and
+    /// returns it.
+    fn assert_bounded<T: Float>(name: &str, value: &Bounded<T>, exact: &(BigInt, BigInt)) -> f64 {
+        // the reference is rounded as well, with its own bound
+        let reference = ratio::<Bounded<T>>(exact);
+        let error = relative_error(value.value(), reference.value());
+
+        let bound = value.relative_error() + reference.relative_error();
+        assert!(error <= bound, "{name}: {error:e} > {bound:e}");
+        error
+    }
+
+    #[cfg(feature = "double-double")]
+    #[test]
+    fn test_dd_product_chain() {
+        use crate::float::{Backend, DdNum};
+
+        // products of rounded quotients, equal to (1/3)^N
+        const N: i64 = 40;
+        let mut product = Bounded::<DdNum>::from(1);
+        for i in 0..N {
+            let factor = Bounded::from(2 * i + 1) / Bounded::from(6 * i + 3);
+            product *= &factor;
+        }
+        let exact = (BigInt::from(1), BigInt::from(3).pow(N as u32));
+        let error = assert_bounded("product chain", &product, &exact);
+        // the double-word operations are not correctly rounded to 106 bits
+        assert!(error > 2f64.powi(2 - Backend::DoubleDouble.precision() as i32));
+    }
+
+    macro_rules! float_test {
+        ($name:ident: $type:ty) => {
+            mod $name {
+                use super::PARAMS;
+                use crate::bound::{self, Bounded};
+                use crate::float::BigInt;
+                use crate::scheme::{self, Exact, Scheme};
+
+                #[test]
+                fn test_bounds() {
+                    type B = Bounded<$type>;
+
+                    for ((p, t, w), ts) in PARAMS {
+                        let scheme = Scheme::cross(p, t, w);
+                        let prob_beta = scheme::prob_beta::<B>(&scheme, ts);
+                        let prob_b = scheme::prob_b::<B>(&scheme, ts);
+                        let (alpha, prob_b_new) = scheme::prob_b_new::<B>(&scheme, ts);
+                        let cost = scheme::attack_cost::<B>(&scheme, ts);
+                        let (_, cost_new) = scheme::attack_new_cost::<B>(&scheme, ts);
+                        let exact = Exact::new(&scheme);
+
+                        let check = |name: &str, value: &B, exact: &(BigInt, BigInt)| {
+                            super::assert_bounded(
+                                &format!("{name} at {ts} for {scheme:?}"),
+                                value,
+                                exact,
+                            )
+                        };
+                        check("prob_beta", &prob_beta, &exact.prob_beta(ts));
+                        check("prob_b", &prob_b, &exact.prob_b(ts));
+                        check("prob_b_new", &prob_b_new, &exact.prob_b_new(ts, alpha));
+                        check("attack_cost", &cost, &exact.attack_cost(ts));
+                        check(
+                            "attack_new_cost",
+                            &cost_new,
+                            &exact.attack_new_cost(ts, alpha),
+                        );
+                        assert_eq!(bound::attack_cost::<$type>(&scheme, ts), bound::bits(&cost));
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum,
+    }
+}
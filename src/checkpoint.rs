@@ -0,0 +1,202 @@
+//! Resumable estimates, recording the cost of every `t*` as soon as it is computed.
+//!
+//! On the largest parameter sets [`estimate_attack_new`](crate::scheme::estimate_attack_new) runs
+//! for a long time. A [`Checkpoint`] keeps the exact cost of each `t*` already evaluated, so that
+//! after an interruption only the missing values of `t*` are evaluated, and the optimum is the
+//! same as the one of an uninterrupted run. Storing the checkpoint is left to the caller, e.g. by
+//! serializing it with the **serde** feature.
+
+use crate::float::{Backend, Float};
+use crate::scheme::{attack_cost, attack_new_cost, Scheme};
+use crate::{get_default_pb_style, ESTIMATOR_VERSION, VERSION};
+use indicatif::ParallelProgressIterator;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Costs of the attacks on a scheme for the values of `t*` evaluated so far.
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "T: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Checkpoint<T> {
+    /// Scheme under attack.
+    pub scheme: Scheme,
+    /// Backend used for the costs.
+    pub backend: Backend,
+    /// Version of the crate that computed the costs.
+    pub version: String,
+    /// Version of the estimators that computed the costs, see [`ESTIMATOR_VERSION`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub estimator_version: u32,
+    /// Cost of the original attack for each evaluated `t*`.
+    pub original: BTreeMap<i64, T>,
+    /// Optimal `alpha` and cost of the new attack for each evaluated `t*`.
+    pub new: BTreeMap<i64, (i64, T)>,
+}
+
+impl<T> Checkpoint<T> {
+    /// Returns [`true`] if the costs were computed for `scheme` with `backend` by this version of
+    /// the estimators, and can therefore be resumed.
+    pub fn matches(&self, scheme: &Scheme, backend: Backend) -> bool {
+        self.scheme == *scheme
+            && self.backend == backend
+            && self.estimator_version == ESTIMATOR_VERSION
+    }
+}
+
+impl<T: Float> Checkpoint<T> {
+    /// Empty checkpoint for `scheme` with `backend`.
+    pub fn new(scheme: Scheme, backend: Backend) -> Self {
+        Checkpoint {
+            scheme,
+            backend,
+            version: VERSION.to_string(),
+            estimator_version: ESTIMATOR_VERSION,
+            original: BTreeMap::new(),
+            new: BTreeMap::new(),
+        }
+    }
+
+    /// As [`scheme::estimate_attack`](crate::scheme::estimate_attack), but only evaluating the
+    /// values of `t*` missing from the checkpoint.
+    ///
+    /// `save` is called with the updated checkpoint after each new value of `t*`.
+    pub fn estimate_attack(
+        &mut self,
+        quiet: bool,
+        save: impl FnMut(&Checkpoint<T>) + Send,
+    ) -> (i64, f64) {
+        let scheme = self.scheme;
+        let missing: Vec<i64> = (0..=scheme.t)
+            .filter(|ts| !self.original.contains_key(ts))
+            .collect();
+
+        let state = Mutex::new((&mut *self, save));
+        missing
+            .into_par_iter()
+            .progress_with_style(get_default_pb_style(quiet))
+            .for_each(|ts| {
+                let comp = attack_cost::<T>(&scheme, ts);
+
+                let mut state = state.lock().unwrap();
+                let (checkpoint, save) = &mut *state;
+                checkpoint.original.insert(ts, comp);
+                save(checkpoint);
+            });
+
+        // the first minimum in order of t*, as in the uninterrupted estimate
+        let (ts, comp) = self
+            .original
+            .iter()
+            .min_by(|(_, comp_a), (_, comp_b)| comp_a.partial_cmp(comp_b).unwrap())
+            .unwrap();
+
+        (*ts, comp.log2())
+    }
+
+    /// As [`scheme::estimate_attack_new`](crate::scheme::estimate_attack_new), but only
+    /// evaluating the values of `t*` missing from the checkpoint.
+    ///
+    /// `save` is called with the updated checkpoint after each new value of `t*`.
+    pub fn estimate_attack_new(
+        &mut self,
+        quiet: bool,
+        save: impl FnMut(&Checkpoint<T>) + Send,
+    ) -> (i64, i64, f64) {
+        let scheme = self.scheme;
+        let missing: Vec<i64> = (0..=scheme.t)
+            .filter(|ts| !self.new.contains_key(ts))
+            .collect();
+
+        let state = Mutex::new((&mut *self, save));
+        missing
+            .into_par_iter()
+            .progress_with_style(get_default_pb_style(quiet))
+            .for_each(|ts| {
+                let result = attack_new_cost::<T>(&scheme, ts);
+
+                let mut state = state.lock().unwrap();
+                let (checkpoint, save) = &mut *state;
+                checkpoint.new.insert(ts, result);
+                save(checkpoint);
+            });
+
+        // the first minimum in order of t*, as in the uninterrupted estimate
+        let (ts, (aa, comp)) = self
+            .new
+            .iter()
+            .min_by(|(_, (_, comp_a)), (_, (_, comp_b))| comp_a.partial_cmp(comp_b).unwrap())
+            .unwrap();
+
+        (*ts, *aa, comp.log2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    macro_rules! float_test {
+        ($name:ident: $type:ty, $backend:expr) => {
+            mod $name {
+                use crate::checkpoint::Checkpoint;
+                use crate::scheme::{self, Scheme};
+                use crate::ESTIMATOR_VERSION;
+
+                #[test]
+                fn test_resume() {
+                    let scheme = Scheme::cross(13, 24, 18);
+                    let mut full = Checkpoint::<$type>::new(scheme, $backend);
+                    let mut saves = 0;
+                    let original = full.estimate_attack(true, |_| saves += 1);
+                    let new = full.estimate_attack_new(true, |_| saves += 1);
+
+                    assert_eq!(saves, 2 * 25);
+                    assert_eq!(original, scheme::estimate_attack::<$type>(&scheme, true));
+                    assert_eq!(new, scheme::estimate_attack_new::<$type>(&scheme, true));
+
+                    // an interrupted run only kept the even values of t*
+                    let mut partial = Checkpoint::<$type>::new(scheme, $backend);
+                    assert!(partial.matches(&scheme, $backend));
+                    assert!(!partial.matches(&Scheme::cross(13, 24, 17), $backend));
+                    let stale = Checkpoint::<$type> {
+                        estimator_version: ESTIMATOR_VERSION - 1,
+                        ..Checkpoint::new(scheme, $backend)
+                    };
+                    assert!(!stale.matches(&scheme, $backend));
+                    partial.original = std::mem::take(&mut full.original);
+                    partial.new = std::mem::take(&mut full.new);
+                    partial.original.retain(|ts, _| ts % 2 == 0);
+                    partial.new.retain(|ts, _| ts % 2 == 0);
+
+                    let mut saves = 0;
+                    assert_eq!(partial.estimate_attack(true, |_| saves += 1), original);
+                    assert_eq!(partial.estimate_attack_new(true, |_| saves += 1), new);
+                    assert_eq!(saves, 2 * 12);
+                }
+            }
+        };
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty, $backend:expr,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type, $backend }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num, crate::float::Backend::F64,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num, crate::float::Backend::ExtF64,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum, crate::float::Backend::Rug,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum, crate::float::Backend::Dashu,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num, crate::float::Backend::F128,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum, crate::float::Backend::DoubleDouble,
+    }
+}
@@ -0,0 +1,473 @@
+//! Forgery estimates for second challenges over a larger alphabet with a fixed composition.
+//!
+//! Here the second challenge is a string of `t` symbols in `{0, ..., N-1}` drawn uniformly among
+//! those with exactly `counts[s]` occurrences of each symbol `s`. The binary fixed-weight challenge
+//! of CROSS is the composition `[t - w, w]`, for which the functions of this module reproduce the
+//! ones in [`scheme`](crate::scheme) exactly.
+//!
+//! In the new attack the adversary guesses a string with composition `guess`, and can answer the
+//! second challenge if the two strings agree outside the rounds where the first challenge was
+//! guessed. Generalising `alpha >= w`, the guess is searched among the compositions with at least
+//! `counts[s]` occurrences of every non-zero symbol `s`.
+//!
+//! The cost of the computation grows quickly with the size of the alphabet, and is only practical
+//! for small `t` when `N > 2`.
+
+use crate::float::{BigInt, Float};
+use crate::scheme::{self, Scheme};
+use crate::{get_default_pb_style, Binomials};
+use indicatif::ParallelProgressIterator;
+use rayon::prelude::*;
+
+/// Description of a signature scheme with a fixed-composition second challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Composition {
+    /// Size of the first challenge space of a single round.
+    pub first_challenges: i64,
+    /// Number of rounds with second challenge equal to each symbol.
+    pub counts: Vec<i64>,
+}
+
+impl Composition {
+    /// Description of a scheme whose second challenge has `counts[s]` occurrences of each symbol
+    /// `s`, or an error if there are less than two symbols, a negative count, more than 65535
+    /// rounds or less than two first challenges.
+    ///
+    /// # Arguments
+    ///
+    /// * `first_challenges` - Size of the first challenge space of a single round.
+    /// * `counts` - Number of rounds with second challenge equal to each symbol.
+    pub fn new(first_challenges: i64, counts: Vec<i64>) -> Result<Self, String> {
+        if counts.len() < 2 {
+            Err("the composition needs the counts of at least two symbols".to_string())
+        } else if counts.iter().any(|&count| count < 0) {
+            Err("the counts of the composition must be non-negative".to_string())
+        } else if counts.iter().sum::<i64>() > u16::MAX as i64 {
+            Err("t must be between 0 and 65535".to_string())
+        } else if first_challenges < 2 {
+            Err("the first challenge space must have at least two elements".to_string())
+        } else {
+            Ok(Composition {
+                first_challenges,
+                counts,
+            })
+        }
+    }
+
+    /// Description of CROSS over `Fp`, with `w` ones in the second challenge.
+    pub fn cross(p: i64, t: i64, w: i64) -> Self {
+        Composition {
+            first_challenges: p - 1,
+            counts: vec![t - w, w],
+        }
+    }
+
+    /// Number of parallel rounds.
+    pub fn t(&self) -> i64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// Multinomial coefficient `n! / (x[0]! ... x[N-1]!)`, assuming `x` sums to `n`.
+///
+/// The factor of symbol 0 is always one and is skipped, so that for binary compositions this is
+/// exactly `binom(n, x[1])`.
+fn multinom(binomials: &Binomials, n: i64, x: &[i64]) -> BigInt {
+    let mut rest = n - x[1];
+    x[2..]
+        .iter()
+        .fold(binomials.get(n, x[1]).clone(), |acc, &xs| {
+            let factor = binomials.get(rest, xs);
+            rest -= xs;
+            acc * factor
+        })
+}
+
+/// Enumerate the compositions `x` of `n` with `lo[s] <= x[s] <= hi[s]`, in lexicographic order of
+/// `(x[1], ..., x[N-1])`.
+fn compositions(n: i64, lo: &[i64], hi: &[i64]) -> Vec<Vec<i64>> {
+    fn rec(s: usize, rest: i64, lo: &[i64], hi: &[i64], x: &mut Vec<i64>, out: &mut Vec<Vec<i64>>) {
+        if s == lo.len() {
+            if lo[0] <= rest && rest <= hi[0] {
+                x[0] = rest;
+                out.push(x.clone());
+            }
+            return;
+        }
+        // the symbols after s (and symbol 0) must be able to absorb what is left
+        let lo_after: i64 = lo[s + 1..].iter().sum::<i64>() + lo[0];
+        let hi_after: i64 = hi[s + 1..].iter().sum::<i64>() + hi[0];
+        for xs in lo[s].max(rest - hi_after)..=hi[s].min(rest - lo_after) {
+            x[s] = xs;
+            rec(s + 1, rest - xs, lo, hi, x, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    rec(1, n, lo, hi, &mut vec![0; lo.len()], &mut out);
+    out
+}
+
+fn sub(a: &[i64], b: &[i64]) -> Vec<i64> {
+    a.iter().zip(b).map(|(a, b)| a - b).collect()
+}
+
+fn first_phase(composition: &Composition) -> Scheme {
+    // the weight does not affect the first phase
+    Scheme {
+        t: composition.t(),
+        w: 0,
+        first_challenges: composition.first_challenges,
+        second_challenges: 2,
+    }
+}
+
+/// Probability that at least `ts` first challenges are guessed correctly.
+#[inline]
+pub fn prob_beta<T: Float>(composition: &Composition, ts: i64) -> T {
+    scheme::prob_beta(&first_phase(composition), ts)
+}
+
+/// Success probability of the second phase of the original attack, given the success of the first.
+#[inline]
+pub fn prob_b<T: Float>(composition: &Composition, ts: i64) -> T {
+    let t = composition.t();
+    let c = &composition.counts;
+    let q = T::from(composition.first_challenges);
+    let inv_q = T::one() / q;
+    let one_minus_inv_q = T::one() - &inv_q;
+    let binomials = Binomials::new(t);
+    let scale = T::from_bigint(&multinom(&binomials, t, c).pow(2));
+    let zero = vec![0; c.len()];
+
+    let sum: T = (ts..=t)
+        .map(|j| {
+            // composition of the challenge inside S, summed exactly
+            let exact: BigInt = compositions(j, &zero, c)
+                .iter()
+                .map(|y| {
+                    multinom(&binomials, j, y).pow(2) * multinom(&binomials, t - j, &sub(c, y))
+                })
+                .sum();
+
+            T::from_bigint(&(exact * binomials.get(t, j)))
+                * inv_q.pow(j as u32)
+                * one_minus_inv_q.pow((t - j) as u32)
+                / &scale
+        })
+        .sum();
+
+    let result = sum / prob_beta::<T>(composition, ts);
+    if result.is_nan() {
+        // TODO: warn of NaN
+        T::zero()
+    } else {
+        result
+    }
+}
+
+#[inline]
+fn sum_b_new<T: Float>(
+    composition: &Composition,
+    binomials: &Binomials,
+    ts: i64,
+    guess: &[i64],
+    inv_q: &T,
+    one_minus_inv_q: &T,
+) -> T {
+    let t = composition.t();
+    let c = &composition.counts;
+    let zero = vec![0; c.len()];
+    let hi: Vec<i64> = guess.iter().zip(c).map(|(a, c)| *a.min(c)).collect();
+
+    (ts..=t)
+        .map(|j| {
+            // composition of both strings outside S, summed exactly
+            let exact: BigInt = compositions(t - j, &zero, &hi)
+                .iter()
+                .map(|m| {
+                    multinom(binomials, t - j, m)
+                        * multinom(binomials, j, &sub(guess, m))
+                        * multinom(binomials, j, &sub(c, m))
+                })
+                .sum();
+
+            T::from_bigint(&(exact * binomials.get(t, j)))
+                * inv_q.pow(j as u32)
+                * one_minus_inv_q.pow((t - j) as u32)
+        })
+        .sum::<T>()
+        / T::from_bigint(&multinom(binomials, t, guess))
+}
+
+/// Success probability of the second phase of the new attack, given the success of the first,
+/// together with the optimal composition of the guess.
+#[inline]
+pub fn prob_b_new<T: Float>(composition: &Composition, ts: i64) -> (Vec<i64>, T) {
+    let t = composition.t();
+    let c = &composition.counts;
+    let q = T::from(composition.first_challenges);
+    let inv_q = T::one() / q;
+    let one_minus_inv_q = T::one() - &inv_q;
+    let beta_prob = prob_beta::<T>(composition, ts);
+    let binomials = Binomials::new(t);
+    let multinom_t_c = T::from_bigint(&multinom(&binomials, t, c));
+
+    let mut lo = c.clone();
+    lo[0] = 0;
+    let hi = vec![t; c.len()];
+
+    let result = compositions(t, &lo, &hi)
+        .into_iter()
+        .map(|guess| {
+            let prob = sum_b_new::<T>(
+                composition,
+                &binomials,
+                ts,
+                &guess,
+                &inv_q,
+                &one_minus_inv_q,
+            );
+            (guess, prob)
+        })
+        .max_by(|(_, comp_a), (_, comp_b)| comp_a.partial_cmp(comp_b).unwrap())
+        .unwrap();
+
+    let prob = result.1 / (beta_prob * multinom_t_c);
+    if prob.is_nan() {
+        // TODO: warn of NaN
+        (result.0, T::zero())
+    } else {
+        (result.0, prob)
+    }
+}
+
+/// Success probability of the second phase of the new attack, given the success of the first,
+/// when guessing a string with composition `guess`.
+#[inline]
+pub fn prob_b_new_guess<T: Float>(composition: &Composition, ts: i64, guess: &[i64]) -> T {
+    let t = composition.t();
+    let q = T::from(composition.first_challenges);
+    let inv_q = T::one() / q;
+    let one_minus_inv_q = T::one() - &inv_q;
+    let beta_prob = prob_beta::<T>(composition, ts);
+    let binomials = Binomials::new(t);
+    let multinom_t_c = T::from_bigint(&multinom(&binomials, t, &composition.counts));
+
+    let prob = sum_b_new::<T>(composition, &binomials, ts, guess, &inv_q, &one_minus_inv_q)
+        / (beta_prob * multinom_t_c);
+    if prob.is_nan() {
+        // TODO: warn of NaN
+        T::zero()
+    } else {
+        prob
+    }
+}
+
+/// Estimate cost of the original attack on `composition`.
+///
+/// Returns the optimal `t*` and the cost as log2 of the estimated gate count. If `quiet` is
+/// [`true`] the progress bar is hidden.
+#[inline]
+pub fn estimate_attack<T: Float>(composition: &Composition, quiet: bool) -> (i64, f64) {
+    let result = (0..=u16::try_from(composition.t()).unwrap())
+        .into_par_iter()
+        .progress_with_style(get_default_pb_style(quiet))
+        .map(|ts| {
+            let beta_prob = prob_beta::<T>(composition, ts as i64);
+            let b_prob = prob_b::<T>(composition, ts as i64);
+
+            let comp: T = T::one() / beta_prob + T::one() / b_prob;
+
+            (comp, ts)
+        })
+        .min_by(|(comp_a, _), (comp_b, _)| comp_a.partial_cmp(comp_b).unwrap())
+        .unwrap();
+
+    let complog = result.0.log2();
+    let ts = result.1;
+
+    (ts as i64, complog)
+}
+
+/// Estimate cost of the new attack on `composition`.
+///
+/// Returns the optimal `t*` and composition of the guess, and the cost as log2 of the estimated
+/// gate count. If `quiet` is [`true`] the progress bar is hidden.
+#[inline]
+pub fn estimate_attack_new<T: Float>(
+    composition: &Composition,
+    quiet: bool,
+) -> (i64, Vec<i64>, f64) {
+    let result = (0..=u16::try_from(composition.t()).unwrap())
+        .into_par_iter()
+        .progress_with_style(get_default_pb_style(quiet))
+        .map(|ts| {
+            let beta_prob = prob_beta::<T>(composition, ts as i64);
+            let (guess, b_prob) = prob_b_new::<T>(composition, ts as i64);
+
+            let comp = T::one() / beta_prob + T::one() / b_prob;
+
+            (comp, ts, guess)
+        })
+        .min_by(|(comp_a, _, _), (comp_b, _, _)| comp_a.partial_cmp(comp_b).unwrap())
+        .unwrap();
+
+    let complog = result.0.log2();
+    let ts = result.1;
+    let guess = result.2;
+
+    (ts as i64, guess, complog)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Composition;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            Composition::new(126, vec![24 - 16, 16]),
+            Ok(Composition::cross(127, 24, 16))
+        );
+        assert!(Composition::new(126, vec![40]).is_err());
+        assert!(Composition::new(126, vec![]).is_err());
+        assert!(Composition::new(126, vec![40, -1, 2]).is_err());
+        assert!(Composition::new(1, vec![24 - 16, 16]).is_err());
+        assert!(Composition::new(126, vec![40000, 30000]).is_err());
+    }
+
+    use super::compositions;
+
+    #[test]
+    fn test_compositions() {
+        assert_eq!(
+            compositions(3, &[0, 0, 1], &[3, 1, 3]),
+            vec![
+                vec![2, 0, 1],
+                vec![1, 0, 2],
+                vec![0, 0, 3],
+                vec![1, 1, 1],
+                vec![0, 1, 2],
+            ]
+        );
+        assert_eq!(
+            compositions(4, &[0, 0], &[1, 4]),
+            vec![vec![1, 3], vec![0, 4]]
+        );
+    }
+
+    macro_rules! float_test {
+        ($name:ident: $type:ty) => {
+            mod $name {
+                use crate::composition::{self, Composition};
+                use crate::float::Float;
+                use crate::scheme::{self, Scheme};
+
+                /// Exact success probability of the second phase by enumeration of all strings.
+                fn brute_force(q: i64, counts: &[i64], ts: i64, guess: &[i64]) -> f64 {
+                    let t = counts.iter().sum::<i64>() as u32;
+                    let n = counts.len() as u32;
+                    let strings = |comp: &[i64]| -> Vec<Vec<u32>> {
+                        (0..n.pow(t))
+                            .map(|mut x| {
+                                (0..t)
+                                    .map(|_| {
+                                        let s = x % n;
+                                        x /= n;
+                                        s
+                                    })
+                                    .collect::<Vec<u32>>()
+                            })
+                            .filter(|v| {
+                                (0..n).all(|s| {
+                                    v.iter().filter(|&&x| x == s).count() as i64 == comp[s as usize]
+                                })
+                            })
+                            .collect()
+                    };
+                    let (challenges, guesses) = (strings(counts), strings(guess));
+
+                    let (mut first, mut second) = (0.0, 0.0);
+                    for set in 0..(1u32 << t) {
+                        let j = set.count_ones() as i32;
+                        if (j as i64) < ts {
+                            continue;
+                        }
+                        let prob =
+                            (1.0 / q as f64).powi(j) * (1.0 - 1.0 / q as f64).powi(t as i32 - j);
+                        let agree = challenges
+                            .iter()
+                            .flat_map(|b| guesses.iter().map(move |g| (b, g)))
+                            .filter(|(b, g)| {
+                                (0..t).all(|i| set >> i & 1 == 1 || b[i as usize] == g[i as usize])
+                            })
+                            .count();
+                        first += prob;
+                        second += prob * agree as f64 / (challenges.len() * guesses.len()) as f64;
+                    }
+
+                    second / first
+                }
+
+                #[test]
+                fn test_binary() {
+                    for (p, t, w, ts) in [(127, 64, 40, 12), (509, 40, 30, 3), (13, 24, 18, 7)] {
+                        let binary = Scheme::cross(p, t, w);
+                        let composition = Composition::cross(p, t, w);
+                        let (guess, prob) = composition::prob_b_new::<$type>(&composition, ts);
+                        let (aa, prob_binary) = scheme::prob_b_new::<$type>(&binary, ts);
+
+                        assert_eq!(
+                            composition::prob_b::<$type>(&composition, ts),
+                            scheme::prob_b::<$type>(&binary, ts)
+                        );
+                        assert_eq!(guess, vec![t - aa, aa]);
+                        assert_eq!(prob, prob_binary);
+                    }
+                }
+
+                #[test]
+                fn test_ternary() {
+                    let q = 3;
+                    let counts = [2, 2, 2];
+                    let composition = Composition {
+                        first_challenges: q,
+                        counts: counts.to_vec(),
+                    };
+                    for ts in 0..=3 {
+                        for guess in [[2, 2, 2], [1, 3, 2], [0, 2, 4]] {
+                            let prob =
+                                composition::prob_b_new_guess::<$type>(&composition, ts, &guess);
+                            let expected = brute_force(q, &counts, ts, &guess);
+
+                            assert!((prob.log2() - expected.log2()).abs() < 1e-6);
+                        }
+                        let prob = composition::prob_b::<$type>(&composition, ts);
+                        let expected = brute_force(q, &counts, ts, &counts);
+                        assert!((prob.log2() - expected.log2()).abs() < 1e-6);
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum,
+    }
+}
@@ -0,0 +1,199 @@
+//! Recipe of the attacks at given `t*` and `alpha`, spelling out what the adversary does in each
+//! phase instead of a single cost.
+//!
+//! In the first phase, the adversary guesses the first challenge of `t*` rounds and recomputes
+//! the first hash until at least `t*` of them are right, the set `S` of rounds guessed correctly
+//! having `|S| = j >= t*`. In the second phase, it guesses a second challenge with `alpha`
+//! non-zero rounds and recomputes the second hash until the challenge it gets agrees with the
+//! guess outside of `S`, as it can answer any second challenge in the rounds of `S`. The original
+//! attack guesses exactly `w` non-zero rounds, while the new one may guess `alpha >= w`.
+
+use crate::float::Float;
+use crate::scheme::{self, term_b, term_b_new, FirstGuess, Scheme};
+use crate::Binomials;
+
+/// Probabilities that exactly `j` first challenges are guessed correctly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CardS {
+    /// Number `j = |S|` of first challenges guessed correctly.
+    pub j: i64,
+    /// Probability of `|S| = j` given the success of the first phase.
+    pub given_first: f64,
+    /// Probability of `|S| = j` given the success of both phases, i.e. of the forgery.
+    pub given_forgery: f64,
+}
+
+/// Recipe of an attack, see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plan {
+    /// Number of rounds `t*` whose first challenge must be guessed correctly.
+    pub ts: i64,
+    /// Number `alpha` of non-zero second challenges guessed, `w` for the original attack.
+    pub alpha: i64,
+    /// Success probability of the first phase, as log2.
+    pub prob_first: f64,
+    /// Success probability of the second phase given the success of the first, as log2.
+    pub prob_second: f64,
+    /// Expected number of first challenges guessed correctly, given the success of the first
+    /// phase.
+    pub expected_rounds: f64,
+    /// Distribution of `|S|` for every `j` from `t*` to `t`.
+    pub distribution: Vec<CardS>,
+}
+
+impl Plan {
+    /// Expected number of attempts of the first phase, as log2.
+    pub fn first_attempts(&self) -> f64 {
+        -self.prob_first
+    }
+
+    /// Expected number of attempts of the second phase, as log2.
+    pub fn second_attempts(&self) -> f64 {
+        -self.prob_second
+    }
+
+    /// Cost of the attack as log2 of the estimated gate count, the sum of the attempts of both
+    /// phases.
+    pub fn cost(&self) -> f64 {
+        let (a, b) = (self.first_attempts(), self.second_attempts());
+        let (max, min) = if a >= b { (a, b) } else { (b, a) };
+        max + (min - max).exp2().ln_1p() / std::f64::consts::LN_2
+    }
+}
+
+/// Logarithm to base 2 of `x`, which is `-inf` for zero whatever the backend.
+fn log2<T: Float>(x: &T) -> f64 {
+    if *x == T::zero() {
+        f64::NEG_INFINITY
+    } else {
+        x.log2()
+    }
+}
+
+/// Recipe of the attack on `scheme` guessing `ts` first challenges and `alpha` non-zero second
+/// challenges, or `w` of them as the original attack if [`None`].
+///
+/// # Arguments
+///
+/// * `scheme` - Parameters of the scheme, see [`Scheme::cross`] for CROSS.
+/// * `ts` - Number of rounds `t*` guessed in the first phase.
+/// * `alpha` - Number of non-zero second challenges guessed by the new attack, or [`None`] for
+///   the original attack.
+pub fn explain<T: Float>(scheme: &Scheme, ts: i64, alpha: Option<i64>) -> Plan {
+    let Scheme { t, w, .. } = *scheme;
+    let guess = FirstGuess::<T>::new(scheme);
+    let binomials = Binomials::new(t);
+    let beta_prob = scheme::prob_beta::<T>(scheme, ts);
+
+    // the terms of the second phase are divided by this scale, as in prob_b and prob_b_new_alpha
+    let scale = match alpha {
+        None => binomials.get(t, w).pow(2),
+        Some(aa) => binomials.get(t, aa) * binomials.get(t, w),
+    } * scheme.second_scale();
+    let scale = T::from_bigint(&scale);
+
+    let mut b_prob = T::zero();
+    let mut terms = Vec::with_capacity((t - ts + 1) as usize);
+    for j in ts..=t {
+        let prob_j = guess.term(T::from_bigint(binomials.get(t, j)), t, j) / &beta_prob;
+        let term = match alpha {
+            None => term_b(scheme, &binomials, &guess, j),
+            Some(aa) => term_b_new(scheme, &binomials, &guess, j, aa),
+        };
+        let term = term / &scale / &beta_prob;
+
+        terms.push((j, log2(&prob_j), log2(&term)));
+        b_prob += &term;
+    }
+    let prob_second = log2(&b_prob);
+
+    let distribution: Vec<CardS> = terms
+        .into_iter()
+        .map(|(j, log2_prob_j, log2_term)| CardS {
+            j,
+            given_first: log2_prob_j.exp2(),
+            given_forgery: (log2_term - prob_second).exp2(),
+        })
+        .collect();
+    let expected_rounds = distribution
+        .iter()
+        .map(|card| card.j as f64 * card.given_first)
+        .sum();
+
+    Plan {
+        ts,
+        alpha: alpha.unwrap_or(w),
+        prob_first: log2(&beta_prob),
+        prob_second,
+        expected_rounds,
+        distribution,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    macro_rules! float_test {
+        ($name:ident: $type:ty) => {
+            mod $name {
+                use crate::explain::explain;
+                use crate::float::Float;
+                use crate::scheme::{self, Scheme};
+
+                const TOLERANCE: f64 = 1e-6;
+
+                #[test]
+                fn test_explain() {
+                    let scheme = Scheme::cross(13, 24, 16);
+                    let ts = 4;
+
+                    let plan = explain::<$type>(&scheme, ts, None);
+                    let prob_b = scheme::prob_b::<$type>(&scheme, ts).log2();
+                    assert!((plan.prob_second - prob_b).abs() < TOLERANCE);
+                    let prob_beta = scheme::prob_beta::<$type>(&scheme, ts).log2();
+                    assert!((plan.prob_first - prob_beta).abs() < TOLERANCE);
+                    let cost = scheme::attack_cost::<$type>(&scheme, ts).log2();
+                    assert!((plan.cost() - cost).abs() < TOLERANCE);
+                    assert_eq!(plan.alpha, 16);
+
+                    let plan = explain::<$type>(&scheme, ts, Some(20));
+                    let prob_b_new = scheme::prob_b_new_alpha::<$type>(&scheme, ts, 20).log2();
+                    assert!((plan.prob_second - prob_b_new).abs() < TOLERANCE);
+                    assert_eq!(plan.alpha, 20);
+
+                    assert_eq!(plan.distribution.len(), 21);
+                    assert_eq!(plan.distribution[0].j, ts);
+                    let first: f64 = plan.distribution.iter().map(|card| card.given_first).sum();
+                    let forgery: f64 = plan
+                        .distribution
+                        .iter()
+                        .map(|card| card.given_forgery)
+                        .sum();
+                    assert!((first - 1.0).abs() < TOLERANCE);
+                    assert!((forgery - 1.0).abs() < TOLERANCE);
+                    assert!(plan.expected_rounds >= ts as f64);
+                    assert!(plan.expected_rounds <= scheme.t as f64);
+                }
+            }
+        };
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum,
+    }
+}
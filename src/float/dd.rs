@@ -0,0 +1,347 @@
+//! Software double-double arithmetic with an extended exponent.
+//!
+//! A [`DoubleDouble`] represents `(hi + lo) * 2^exp`, where `hi + lo` is an unevaluated sum of
+//! two [`f64`] carrying about 106 significant bits, normalised so that `1 <= |hi| < 2`. Keeping
+//! the exponent in a separate [`i64`] avoids the overflow of `binom(960, 480)` and the underflow
+//! of `(1/126)^960` that the [`f64`] exponent range cannot represent.
+//!
+//! The algorithms are the classic error-free transformations of Dekker and Knuth, as in the QD
+//! library by Hida, Li and Bailey.
+
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Differences of exponents above this threshold make the smaller operand of a sum negligible.
+const MAX_SHIFT: i64 = 128;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+    exp: i64,
+}
+
+#[inline]
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+#[inline]
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+#[inline]
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+/// Returns `2^e` for `|e| <= 1022`.
+#[inline]
+fn exp2i(e: i64) -> f64 {
+    f64::from_bits(((e + 1023) as u64) << 52)
+}
+
+/// Sum of two unscaled double-double numbers.
+#[inline]
+fn dd_add(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let (s1, s2) = two_sum(a.0, b.0);
+    let (t1, t2) = two_sum(a.1, b.1);
+    let (s1, s2) = quick_two_sum(s1, s2 + t1);
+    quick_two_sum(s1, s2 + t2)
+}
+
+/// Product of an unscaled double-double number by a [`f64`].
+#[inline]
+fn dd_mul_f64(a: (f64, f64), b: f64) -> (f64, f64) {
+    let (p, e) = two_prod(a.0, b);
+    quick_two_sum(p, e + a.1 * b)
+}
+
+impl DoubleDouble {
+    pub const ZERO: DoubleDouble = DoubleDouble {
+        hi: 0.0,
+        lo: 0.0,
+        exp: 0,
+    };
+
+    pub const ONE: DoubleDouble = DoubleDouble {
+        hi: 1.0,
+        lo: 0.0,
+        exp: 0,
+    };
+
+    /// Normalise `(hi + lo) * 2^exp` so that `1 <= |hi| < 2`.
+    fn new(hi: f64, lo: f64, exp: i64) -> Self {
+        if hi == 0.0 {
+            return Self::ZERO;
+        }
+        if !hi.is_finite() {
+            return DoubleDouble {
+                hi,
+                lo: 0.0,
+                exp: 0,
+            };
+        }
+
+        // the operands are normalised, so `hi` is never subnormal
+        let (hi, lo) = quick_two_sum(hi, lo);
+        let e = ((hi.to_bits() >> 52) & 0x7ff) as i64 - 1023;
+        let scale = exp2i(-e);
+        DoubleDouble {
+            hi: hi * scale,
+            lo: lo * scale,
+            exp: exp + e,
+        }
+    }
+
+    /// Returns `value * 2^exp` for `value < 2^127`, exactly if `value` fits in 106 bits.
+    pub fn from_scaled(value: u128, exp: i64) -> Self {
+        let hi = value as f64;
+        let lo = (value as i128 - hi as i128) as f64;
+        DoubleDouble::new(hi, lo, exp)
+    }
+
+    #[cfg(feature = "serde")]
+    /// Returns `(hi + lo) * 2^exp`.
+    pub fn from_parts(hi: f64, lo: f64, exp: i64) -> Self {
+        DoubleDouble::new(hi, lo, exp)
+    }
+
+    #[cfg(feature = "serde")]
+    /// Returns `(hi, lo, exp)` such that `self = (hi + lo) * 2^exp`.
+    pub fn to_parts(self) -> (f64, f64, i64) {
+        (self.hi, self.lo, self.exp)
+    }
+
+    pub fn is_nan(&self) -> bool {
+        self.hi.is_nan()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.hi == 0.0
+    }
+
+    /// Returns `self` to the power `exp`, by repeated squaring.
+    pub fn powi(&self, mut exp: u32) -> Self {
+        let mut base = *self;
+        let mut result = Self::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Logarithm to base 2, rounded to [`f64`].
+    pub fn log2(&self) -> f64 {
+        if !self.hi.is_finite() || self.hi <= 0.0 {
+            return self.hi.log2();
+        }
+        self.hi.log2() + self.lo / self.hi / std::f64::consts::LN_2 + self.exp as f64
+    }
+}
+
+impl From<i64> for DoubleDouble {
+    fn from(value: i64) -> Self {
+        let hi = value as f64;
+        let lo = (value as i128 - hi as i128) as f64;
+        DoubleDouble::new(hi, lo, 0)
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        DoubleDouble {
+            hi: -self.hi,
+            lo: -self.lo,
+            exp: self.exp,
+        }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if !self.hi.is_finite() || !rhs.hi.is_finite() {
+            return DoubleDouble::new(self.hi + rhs.hi, 0.0, 0);
+        }
+        if self.is_zero() {
+            return rhs;
+        }
+        if rhs.is_zero() {
+            return self;
+        }
+
+        let (big, small) = if self.exp >= rhs.exp {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let shift = big.exp - small.exp;
+        if shift > MAX_SHIFT {
+            return big;
+        }
+
+        let scale = exp2i(-shift);
+        let (hi, lo) = dd_add((big.hi, big.lo), (small.hi * scale, small.lo * scale));
+        DoubleDouble::new(hi, lo, big.exp)
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if !self.hi.is_finite() || !rhs.hi.is_finite() {
+            return DoubleDouble::new(self.hi * rhs.hi, 0.0, 0);
+        }
+
+        let (p, e) = two_prod(self.hi, rhs.hi);
+        let e = e + (self.hi * rhs.lo + self.lo * rhs.hi);
+        DoubleDouble::new(p, e, self.exp + rhs.exp)
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if !self.hi.is_finite() || !rhs.hi.is_finite() || rhs.is_zero() {
+            return DoubleDouble::new(self.hi / rhs.hi, 0.0, 0);
+        }
+
+        let a = (self.hi, self.lo);
+        let b = (rhs.hi, rhs.lo);
+        let q1 = a.0 / b.0;
+        let (p1, p2) = dd_mul_f64(b, q1);
+        let r = dd_add(a, (-p1, -p2));
+        let q2 = r.0 / b.0;
+        let (p1, p2) = dd_mul_f64(b, q2);
+        let r = dd_add(r, (-p1, -p2));
+        let q3 = r.0 / b.0;
+
+        let (hi, lo) = dd_add(quick_two_sum(q1, q2), (q3, 0.0));
+        DoubleDouble::new(hi, lo, self.exp - rhs.exp)
+    }
+}
+
+impl PartialEq for DoubleDouble {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for DoubleDouble {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let sign = self.hi.partial_cmp(&0.0)?;
+        let order = sign.cmp(&other.hi.partial_cmp(&0.0)?);
+        if order != Ordering::Equal {
+            return Some(order);
+        }
+
+        // infinities are not normalised, but are larger in magnitude than any finite number
+        if self.exp == other.exp || !self.hi.is_finite() || !other.hi.is_finite() {
+            return match self.hi.partial_cmp(&other.hi)? {
+                Ordering::Equal => self.lo.partial_cmp(&other.lo),
+                order => Some(order),
+            };
+        }
+
+        // same sign and both non-zero, the larger exponent has the larger magnitude
+        match sign {
+            Ordering::Greater => Some(self.exp.cmp(&other.exp)),
+            _ => Some(other.exp.cmp(&self.exp)),
+        }
+    }
+}
+
+forward_ref_ops! {
+    DoubleDouble;
+    Add::add, AddAssign::add_assign;
+    Sub::sub, SubAssign::sub_assign;
+    Mul::mul, MulAssign::mul_assign;
+    Div::div, DivAssign::div_assign;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DoubleDouble;
+
+    #[test]
+    fn test_exact_integers() {
+        // 2^60 + 1 does not fit in the mantissa of a f64
+        let x = DoubleDouble::from((1 << 60) + 1);
+        let y = DoubleDouble::from(1 << 60);
+        assert_eq!(x - y, DoubleDouble::ONE);
+
+        // (2^40 + 1)^2 needs 81 bits
+        let x = DoubleDouble::from((1 << 40) + 1);
+        let y = DoubleDouble::from(1 << 40);
+        assert_eq!(x * x - y * y - y - y, DoubleDouble::ONE);
+    }
+
+    #[test]
+    fn test_precision() {
+        // 1/3 * 3 is exact up to the last bits of the double-double mantissa
+        let three = DoubleDouble::from(3);
+        let err = (DoubleDouble::ONE / three * three - DoubleDouble::ONE).log2();
+        assert!(err < -100.0, "{err}");
+
+        let x = DoubleDouble::from(7) / DoubleDouble::from(10);
+        assert!(x > DoubleDouble::ZERO && x < DoubleDouble::ONE);
+        assert!(-x < x);
+    }
+
+    #[test]
+    fn test_range() {
+        // far beyond the exponent range of f64
+        let small = (DoubleDouble::ONE / DoubleDouble::from(126)).powi(960);
+        assert!((small.log2() + 960.0 * 126f64.log2()).abs() < 1e-9);
+
+        let big = DoubleDouble::from(1 << 40).powi(100);
+        assert_eq!(big.log2(), 4000.0);
+        assert_eq!(big * small / big, small);
+        assert_eq!(big + small, big);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let inf = DoubleDouble::new(f64::INFINITY, 0.0, 0);
+        let big = DoubleDouble::from(1 << 40).powi(100);
+        assert_eq!(inf, inf);
+        assert_eq!(-inf, -inf);
+        assert!(-inf < -big && -big < DoubleDouble::ZERO && big < inf);
+        assert!(DoubleDouble::new(f64::NAN, 0.0, 0)
+            .partial_cmp(&inf)
+            .is_none());
+
+        // only the low part tells these apart
+        let x = DoubleDouble::from((1 << 60) + 1);
+        let y = DoubleDouble::from(1 << 60);
+        assert!(y < x && -x < -y);
+        assert_ne!(x, y);
+        assert_eq!(
+            DoubleDouble::ONE / DoubleDouble::from(2),
+            DoubleDouble::new(0.5, 0.0, 0)
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! [`f64`] arithmetic with an extended exponent.
+//!
+//! An [`ExtendedF64`] represents `m * 2^exp`, where the mantissa `m` is a [`f64`] and the
+//! exponent is a separate [`i64`]. The precision is the one of [`f64`], but the range is large
+//! enough for `binom(960, 480)^2` and `(1/126)^960`.
+//!
+//! To keep the speed of [`f64`], the exponent of the mantissa is not fixed but only kept within
+//! `(-256, 256]`, while `exp` is a multiple of 512. In this way most operations are performed
+//! directly on the mantissas, and the result is renormalised only when it leaves the window.
+
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Step of the exponent.
+const UNIT: i64 = 512;
+
+/// Exponent of zero, so that it is negligible in any sum.
+const ZERO_EXP: i64 = i64::MIN / 2;
+
+/// Exponent of infinities and NaN, so that they absorb any sum.
+const SPECIAL_EXP: i64 = i64::MAX / 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtendedF64 {
+    m: f64,
+    exp: i64,
+}
+
+/// Returns `2^e` for `|e| <= 1022`.
+#[inline]
+fn exp2i(e: i64) -> f64 {
+    f64::from_bits(((e + 1023) as u64) << 52)
+}
+
+impl ExtendedF64 {
+    pub const ZERO: ExtendedF64 = ExtendedF64 {
+        m: 0.0,
+        exp: ZERO_EXP,
+    };
+
+    pub const ONE: ExtendedF64 = ExtendedF64 { m: 1.0, exp: 0 };
+
+    /// Renormalise `m * 2^exp` so that the exponent of `m` lies within `(-256, 256]`.
+    #[inline]
+    fn new(m: f64, exp: i64) -> Self {
+        let e = ((m.to_bits() >> 52) & 0x7ff) as i64 - 1023;
+        if -UNIT / 2 < e && e <= UNIT / 2 {
+            ExtendedF64 { m, exp }
+        } else {
+            Self::rescale(m, exp, e)
+        }
+    }
+
+    #[cold]
+    fn rescale(mut m: f64, exp: i64, e: i64) -> Self {
+        if m == 0.0 {
+            return Self::ZERO;
+        }
+        if !m.is_finite() {
+            return ExtendedF64 {
+                m,
+                exp: SPECIAL_EXP,
+            };
+        }
+
+        // the operands are normalised, so `m` is never subnormal
+        let units = (e + UNIT / 2 - 1).div_euclid(UNIT);
+        let scale = exp2i(-units.signum() * UNIT);
+        for _ in 0..units.abs() {
+            m *= scale;
+        }
+        ExtendedF64 {
+            m,
+            exp: exp + units * UNIT,
+        }
+    }
+
+    #[inline]
+    pub fn is_nan(&self) -> bool {
+        self.m.is_nan()
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.m == 0.0
+    }
+
+    /// Returns `2^e`.
+    #[inline]
+    fn exp2(e: i64) -> Self {
+        let exp = (e + UNIT / 2 - 1).div_euclid(UNIT) * UNIT;
+        ExtendedF64 {
+            m: exp2i(e - exp),
+            exp,
+        }
+    }
+
+    /// Returns `m * 2^exp`.
+    #[inline]
+    pub fn from_parts(m: f64, exp: i64) -> Self {
+        if m == 0.0 || !m.is_finite() {
+            ExtendedF64::new(m, 0)
+        } else {
+            ExtendedF64::new(m, 0) * Self::exp2(exp)
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    /// Returns `(m, exp)` such that `self = m * 2^exp`, with `exp = 0` for zero, infinities and
+    /// NaN.
+    #[inline]
+    pub fn to_parts(self) -> (f64, i64) {
+        if self.exp == ZERO_EXP || self.exp == SPECIAL_EXP {
+            (self.m, 0)
+        } else {
+            (self.m, self.exp)
+        }
+    }
+
+    /// Returns `self` to the power `k`.
+    #[inline]
+    pub fn powi(&self, mut k: u32) -> Self {
+        if self.exp == ZERO_EXP || self.exp == SPECIAL_EXP {
+            return ExtendedF64::new(self.m.powi(k as i32), 0);
+        }
+
+        // m = f * 2^e with 1 <= |f| < 2, the power of two is exact and f^k is computed in chunks
+        // small enough not to overflow
+        let e = ((self.m.to_bits() >> 52) & 0x7ff) as i64 - 1023;
+        let f = self.m * exp2i(-e);
+        let mut result = Self::exp2((self.exp + e) * k as i64);
+        while k > 0 {
+            let chunk = k.min(UNIT as u32);
+            result = result * ExtendedF64::new(f.powi(chunk as i32), 0);
+            k -= chunk;
+        }
+        result
+    }
+
+    /// Logarithm to base 2.
+    #[inline]
+    pub fn log2(&self) -> f64 {
+        if self.exp == ZERO_EXP || self.exp == SPECIAL_EXP {
+            self.m.log2()
+        } else {
+            self.m.log2() + self.exp as f64
+        }
+    }
+}
+
+impl From<i64> for ExtendedF64 {
+    #[inline]
+    fn from(value: i64) -> Self {
+        ExtendedF64::new(value as f64, 0)
+    }
+}
+
+impl Neg for ExtendedF64 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        ExtendedF64 {
+            m: -self.m,
+            exp: self.exp,
+        }
+    }
+}
+
+impl Add for ExtendedF64 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        if self.exp == rhs.exp {
+            return ExtendedF64::new(self.m + rhs.m, self.exp);
+        }
+
+        let (big, small) = if self.exp > rhs.exp {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        // the exponents of the mantissas are within (-256, 256], so with a difference of two
+        // units the smaller operand is below 2^-512 times the larger one
+        if big.exp - small.exp == UNIT {
+            ExtendedF64::new(big.m + small.m * exp2i(-UNIT), big.exp)
+        } else {
+            big
+        }
+    }
+}
+
+impl Sub for ExtendedF64 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl Mul for ExtendedF64 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        ExtendedF64::new(self.m * rhs.m, self.exp.wrapping_add(rhs.exp))
+    }
+}
+
+impl Div for ExtendedF64 {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        ExtendedF64::new(self.m / rhs.m, self.exp.wrapping_sub(rhs.exp))
+    }
+}
+
+impl PartialOrd for ExtendedF64 {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.exp == other.exp {
+            return self.m.partial_cmp(&other.m);
+        }
+
+        let sign = self.m.partial_cmp(&0.0)?;
+        let order = sign.cmp(&other.m.partial_cmp(&0.0)?);
+        if order != Ordering::Equal {
+            return Some(order);
+        }
+
+        // same sign and both non-zero, the larger exponent has the larger magnitude
+        match sign {
+            Ordering::Greater => Some(self.exp.cmp(&other.exp)),
+            _ => Some(other.exp.cmp(&self.exp)),
+        }
+    }
+}
+
+forward_ref_ops! {
+    ExtendedF64;
+    Add::add, AddAssign::add_assign;
+    Sub::sub, SubAssign::sub_assign;
+    Mul::mul, MulAssign::mul_assign;
+    Div::div, DivAssign::div_assign;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExtendedF64;
+    use crate::float::{ExtF64Num, Float};
+
+    #[test]
+    fn test_arithmetic() {
+        let x = ExtendedF64::from(7) / ExtendedF64::from(10);
+        assert!(x > ExtendedF64::ZERO && x < ExtendedF64::ONE);
+        assert!(-x < x);
+        assert_eq!(x * ExtendedF64::from(10), ExtendedF64::from(7));
+        assert_eq!(x - x, ExtendedF64::ZERO);
+        assert_eq!(-ExtendedF64::ZERO, ExtendedF64::ZERO);
+        assert!((ExtendedF64::ZERO / ExtendedF64::ZERO).is_nan());
+    }
+
+    #[test]
+    fn test_range() {
+        // far beyond the exponent range of f64
+        let small = (ExtendedF64::ONE / ExtendedF64::from(126)).powi(960);
+        assert!((small.log2() + 960.0 * 126f64.log2()).abs() < 1e-9);
+
+        let big = ExtendedF64::from(1 << 40).powi(100);
+        assert_eq!(big.log2(), 4000.0);
+        assert_eq!(big * small / big, small);
+        assert_eq!(big + small, big);
+        assert_eq!(ExtendedF64::ZERO.powi(0), ExtendedF64::ONE);
+        assert_eq!((-ExtendedF64::from(3)).powi(3), -ExtendedF64::from(27));
+    }
+
+    #[test]
+    fn test_binom() {
+        // overflows f64, log2(binom(1100, 550)) = 1094.6222801458664...
+        let binom = crate::binom::<ExtF64Num>(1100, 550);
+        assert!((binom.log2() - 1094.6222801458664).abs() < 1e-9);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::float::{Float, FloatAssignOps, FloatOps, MulDivAssign};
+use crate::float::{Backend, BigInt, Float, FloatAssignOps, FloatOps, MulDivAssign};
 use derive_more::{Add, Div, Mul, Sub};
 use num_traits::{One, Zero};
 use std::iter::Sum;
@@ -19,24 +19,28 @@ macro_rules! newfloat {
         }
 
         impl<'a> AddAssign<&'a $name> for $name {
+            #[inline]
             fn add_assign(&mut self, rhs: &'a Self) {
                 self.0 += &rhs.0
             }
         }
 
         impl<'a> SubAssign<&'a $name> for $name {
+            #[inline]
             fn sub_assign(&mut self, rhs: &'a Self) {
                 self.0 -= &rhs.0
             }
         }
 
         impl<'a> MulAssign<&'a $name> for $name {
+            #[inline]
             fn mul_assign(&mut self, rhs: &'a Self) {
                 self.0 *= &rhs.0
             }
         }
 
         impl<'a> DivAssign<&'a $name> for $name {
+            #[inline]
             fn div_assign(&mut self, rhs: &'a Self) {
                 self.0 /= &rhs.0
             }
@@ -45,6 +49,7 @@ macro_rules! newfloat {
         impl<'a> Add<&'a $name> for $name {
             type Output = Self;
 
+            #[inline]
             fn add(self, rhs: &'a Self) -> Self {
                 $name(self.0 + &rhs.0)
             }
@@ -53,6 +58,7 @@ macro_rules! newfloat {
         impl<'a> Sub<&'a $name> for $name {
             type Output = Self;
 
+            #[inline]
             fn sub(self, rhs: &'a Self) -> Self {
                 $name(self.0 - &rhs.0)
             }
@@ -61,6 +67,7 @@ macro_rules! newfloat {
         impl<'a> Mul<&'a $name> for $name {
             type Output = Self;
 
+            #[inline]
             fn mul(self, rhs: &'a Self) -> Self {
                 $name(self.0 * &rhs.0)
             }
@@ -69,6 +76,7 @@ macro_rules! newfloat {
         impl<'a> Div<&'a $name> for $name {
             type Output = Self;
 
+            #[inline]
             fn div(self, rhs: &'a Self) -> Self {
                 $name(self.0 / &rhs.0)
             }
@@ -80,9 +88,63 @@ macro_rules! newfloat {
     };
 }
 
+/// [`f64`] serialized as a number, or as the string `"inf"`, `"-inf"` or `"NaN"`, which JSON
+/// cannot represent as numbers.
+#[cfg(all(feature = "serde", any(feature = "inexact", feature = "double-double")))]
+struct JsonF64(f64);
+
+#[cfg(all(feature = "serde", any(feature = "inexact", feature = "double-double")))]
+impl serde::Serialize for JsonF64 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_finite() {
+            serializer.serialize_f64(self.0)
+        } else {
+            serializer.serialize_str(&self.0.to_string())
+        }
+    }
+}
+
+#[cfg(all(feature = "serde", any(feature = "inexact", feature = "double-double")))]
+impl<'de> serde::Deserialize<'de> for JsonF64 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = JsonF64;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a number, \"inf\", \"-inf\" or \"NaN\"")
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<JsonF64, E> {
+                Ok(JsonF64(value))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<JsonF64, E> {
+                Ok(JsonF64(value as f64))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<JsonF64, E> {
+                Ok(JsonF64(value as f64))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<JsonF64, E> {
+                match value {
+                    "inf" | "-inf" | "NaN" => Ok(JsonF64(value.parse().unwrap())),
+                    _ => Err(E::invalid_value(serde::de::Unexpected::Str(value), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
 #[cfg(feature = "inexact")]
 pub(super) mod inexact {
     use super::*;
+    use crate::float::leading_bits;
+    use num_bigint::Sign;
 
     newfloat! {
         /// Newtype wrapper around primitive [`f64`] for [`Float`] implementation.
@@ -133,6 +195,8 @@ pub(super) mod inexact {
     }
 
     impl Float for F64Num {
+        const BACKEND: Backend = Backend::F64;
+
         fn pow(&self, exp: u32) -> Self {
             F64Num(self.0.powi(exp as i32))
         }
@@ -144,6 +208,148 @@ pub(super) mod inexact {
         fn is_nan(&self) -> bool {
             self.0.is_nan()
         }
+
+        fn from_bigint(value: &BigInt) -> Self {
+            let (top, shift) = leading_bits(value);
+            let magnitude = top as f64 * 2f64.powi(shift.min(i32::MAX as i64) as i32);
+            F64Num(if value.sign() == Sign::Minus {
+                -magnitude
+            } else {
+                magnitude
+            })
+        }
+    }
+
+    /// Serialized as the [`f64`], with infinities and NaN as strings.
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for F64Num {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serde::Serialize::serialize(&JsonF64(self.0), serializer)
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for F64Num {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let JsonF64(value) = serde::Deserialize::deserialize(deserializer)?;
+            Ok(F64Num(value))
+        }
+    }
+}
+
+#[cfg(feature = "inexact")]
+pub(super) mod extended {
+    use super::*;
+    use crate::float::ext::ExtendedF64;
+    use crate::float::leading_bits;
+    use num_bigint::Sign;
+
+    newfloat! {
+        /// Newtype wrapper around a [`f64`] mantissa with an [`i64`] exponent for [`Float`]
+        /// implementation.
+        ///
+        /// As precise as [`F64Num`](super::inexact::F64Num) and within a small factor of its
+        /// speed, but neither overflows nor underflows for large `t`.
+        ExtF64Num: ExtendedF64
+    }
+
+    impl From<i64> for ExtF64Num {
+        #[inline]
+        fn from(value: i64) -> Self {
+            ExtF64Num(ExtendedF64::from(value))
+        }
+    }
+
+    impl Zero for ExtF64Num {
+        #[inline]
+        fn zero() -> Self {
+            ExtF64Num(ExtendedF64::ZERO)
+        }
+
+        #[inline]
+        fn is_zero(&self) -> bool {
+            self.0.is_zero()
+        }
+
+        #[inline]
+        fn set_zero(&mut self) {
+            self.0 = ExtendedF64::ZERO;
+        }
+    }
+
+    impl One for ExtF64Num {
+        #[inline]
+        fn one() -> Self {
+            ExtF64Num(ExtendedF64::ONE)
+        }
+
+        #[inline]
+        fn is_one(&self) -> bool
+        where
+            Self: PartialEq,
+        {
+            self.0 == ExtendedF64::ONE
+        }
+
+        #[inline]
+        fn set_one(&mut self) {
+            self.0 = ExtendedF64::ONE;
+        }
+    }
+
+    impl<'a> MulDivAssign<&'a Self, &'a Self> for ExtF64Num {
+        #[inline]
+        fn mul_div_assign(&mut self, mul: &'a Self, div: &'a Self) {
+            self.0 *= &(mul.0 / div.0)
+        }
+    }
+
+    impl Float for ExtF64Num {
+        const BACKEND: Backend = Backend::ExtF64;
+
+        #[inline]
+        fn pow(&self, exp: u32) -> Self {
+            ExtF64Num(self.0.powi(exp))
+        }
+
+        #[inline]
+        fn log2(&self) -> f64 {
+            self.0.log2()
+        }
+
+        #[inline]
+        fn is_nan(&self) -> bool {
+            self.0.is_nan()
+        }
+
+        #[inline]
+        fn from_bigint(value: &BigInt) -> Self {
+            let (top, shift) = leading_bits(value);
+            let magnitude = ExtendedF64::from_parts(top as f64, shift);
+            ExtF64Num(if value.sign() == Sign::Minus {
+                -magnitude
+            } else {
+                magnitude
+            })
+        }
+    }
+
+    /// Serialized as the exact pair `(m, exp)` of [`ExtendedF64::to_parts`], with infinities and
+    /// NaN as strings.
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for ExtF64Num {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let (m, exp) = self.0.to_parts();
+            serde::Serialize::serialize(&(JsonF64(m), exp), serializer)
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for ExtF64Num {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (JsonF64(m), exp) = serde::Deserialize::deserialize(deserializer)?;
+            Ok(ExtF64Num(ExtendedF64::from_parts(m, exp)))
+        }
     }
 }
 
@@ -151,6 +357,7 @@ pub(super) mod inexact {
 pub(super) mod rug {
     use super::*;
     use ::rug::Float as RugFloat;
+    use num_bigint::Sign;
 
     newfloat! {
         /// Newtype wrapper around [`::rug::Float`] for [`Float`] implementation.
@@ -160,7 +367,7 @@ pub(super) mod rug {
     }
 
     impl RugNum {
-        const PRECISION: u32 = 64;
+        pub(crate) const PRECISION: u32 = 64;
     }
 
     impl From<i64> for RugNum {
@@ -208,6 +415,8 @@ pub(super) mod rug {
     }
 
     impl Float for RugNum {
+        const BACKEND: Backend = Backend::Rug;
+
         fn pow(&self, exp: u32) -> Self {
             let result = RugFloat::with_val(Self::PRECISION, ::rug::ops::Pow::pow(&self.0, exp));
             RugNum(result)
@@ -220,12 +429,39 @@ pub(super) mod rug {
         fn is_nan(&self) -> bool {
             self.0.is_nan()
         }
+
+        fn from_bigint(value: &BigInt) -> Self {
+            let (sign, digits) = value.to_u64_digits();
+            let mut integer = ::rug::Integer::from_digits(&digits, ::rug::integer::Order::Lsf);
+            if sign == Sign::Minus {
+                integer = -integer;
+            }
+            RugNum(RugFloat::with_val(Self::PRECISION, &integer))
+        }
+    }
+
+    /// Serialized as a hexadecimal string with all the significant bits.
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for RugNum {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&self.0.to_string_radix(16, None))
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for RugNum {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+            let parsed = RugFloat::parse_radix(&value, 16).map_err(serde::de::Error::custom)?;
+            Ok(RugNum(RugFloat::with_val(Self::PRECISION, parsed)))
+        }
     }
 }
 
 #[cfg(feature = "dashu")]
 pub(super) mod dashu {
     use super::*;
+    use num_bigint::Sign;
     type FBig = dashu_float::FBig;
 
     newfloat! {
@@ -236,7 +472,7 @@ pub(super) mod dashu {
     }
 
     impl DashuNum {
-        const PRECISION: usize = 32;
+        pub(crate) const PRECISION: usize = 32;
     }
 
     impl From<i64> for DashuNum {
@@ -247,7 +483,7 @@ pub(super) mod dashu {
 
     impl Zero for DashuNum {
         fn zero() -> Self {
-            DashuNum(FBig::ZERO)
+            DashuNum(FBig::ZERO.with_precision(Self::PRECISION).value())
         }
 
         fn is_zero(&self) -> bool {
@@ -255,13 +491,13 @@ pub(super) mod dashu {
         }
 
         fn set_zero(&mut self) {
-            self.0 = FBig::ZERO
+            *self = Self::zero()
         }
     }
 
     impl One for DashuNum {
         fn one() -> Self {
-            DashuNum(FBig::ONE)
+            DashuNum(FBig::ONE.with_precision(Self::PRECISION).value())
         }
 
         fn is_one(&self) -> bool
@@ -272,7 +508,7 @@ pub(super) mod dashu {
         }
 
         fn set_one(&mut self) {
-            self.0 = FBig::ONE
+            *self = Self::one()
         }
     }
 
@@ -283,6 +519,8 @@ pub(super) mod dashu {
     }
 
     impl Float for DashuNum {
+        const BACKEND: Backend = Backend::Dashu;
+
         fn pow(&self, exp: u32) -> Self {
             DashuNum(self.0.powi(exp.into()))
         }
@@ -295,12 +533,56 @@ pub(super) mod dashu {
         fn is_nan(&self) -> bool {
             false
         }
+
+        fn from_bigint(value: &BigInt) -> Self {
+            let magnitude = dashu_int::IBig::from(dashu_int::UBig::from_le_bytes(
+                &value.magnitude().to_bytes_le(),
+            ));
+            let integer = if value.sign() == Sign::Minus {
+                -magnitude
+            } else {
+                magnitude
+            };
+            DashuNum(FBig::from(integer).with_precision(Self::PRECISION).value())
+        }
+    }
+
+    /// Serialized as the exact string `<significand>p<exponent>`, with the significand in
+    /// hexadecimal and the exponent of 2 in decimal.
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for DashuNum {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let repr = self.0.repr();
+            serializer.serialize_str(&format!("{:x}p{}", repr.significand(), repr.exponent()))
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for DashuNum {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            use serde::de::Error;
+
+            let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+            let (significand, exponent) = value
+                .split_once('p')
+                .ok_or_else(|| D::Error::custom("expected <significand>p<exponent>"))?;
+            let significand =
+                dashu_int::IBig::from_str_radix(significand, 16).map_err(D::Error::custom)?;
+            let exponent = exponent.parse().map_err(D::Error::custom)?;
+            Ok(DashuNum(
+                FBig::from_parts(significand, exponent)
+                    .with_precision(Self::PRECISION)
+                    .value(),
+            ))
+        }
     }
 }
 
 #[cfg(feature = "nightly-float")]
 pub(super) mod f128 {
     use super::*;
+    use crate::float::leading_bits;
+    use num_bigint::Sign;
 
     newfloat! {
         /// Newtype wrapper around unstable [`prim@f128`] float type for [`Float`] implementation.
@@ -309,7 +591,7 @@ pub(super) mod f128 {
 
     impl From<i64> for F128Num {
         fn from(value: i64) -> Self {
-            F128Num(f128::from(value as f64))
+            F128Num(value as f128)
         }
     }
 
@@ -351,6 +633,8 @@ pub(super) mod f128 {
     }
 
     impl Float for F128Num {
+        const BACKEND: Backend = Backend::F128;
+
         fn pow(&self, exp: u32) -> Self {
             F128Num(self.0.powi(exp as i32))
         }
@@ -362,5 +646,136 @@ pub(super) mod f128 {
         fn is_nan(&self) -> bool {
             self.0.is_nan()
         }
+
+        fn from_bigint(value: &BigInt) -> Self {
+            let (top, shift) = leading_bits(value);
+            let magnitude = top as f128 * 2f128.powi(shift.min(i32::MAX as i64) as i32);
+            F128Num(if value.sign() == Sign::Minus {
+                -magnitude
+            } else {
+                magnitude
+            })
+        }
+    }
+
+    /// Serialized as the hexadecimal string of the bits of the [`prim@f128`].
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for F128Num {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&format!("{:#034x}", self.0.to_bits()))
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for F128Num {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+            let bits = u128::from_str_radix(value.trim_start_matches("0x"), 16)
+                .map_err(serde::de::Error::custom)?;
+            Ok(F128Num(f128::from_bits(bits)))
+        }
+    }
+}
+
+#[cfg(feature = "double-double")]
+pub(super) mod double_double {
+    use super::*;
+    use crate::float::dd::DoubleDouble;
+    use crate::float::leading_bits;
+    use num_bigint::Sign;
+
+    newfloat! {
+        /// Newtype wrapper around a software double-double float for [`Float`] implementation.
+        ///
+        /// Use about 106 significant bits and a separate [`i64`] exponent, so that neither
+        /// overflow nor underflow occur for large `t`.
+        DdNum: DoubleDouble
+    }
+
+    impl From<i64> for DdNum {
+        fn from(value: i64) -> Self {
+            DdNum(DoubleDouble::from(value))
+        }
+    }
+
+    impl Zero for DdNum {
+        fn zero() -> Self {
+            DdNum(DoubleDouble::ZERO)
+        }
+
+        fn is_zero(&self) -> bool {
+            self.0.is_zero()
+        }
+
+        fn set_zero(&mut self) {
+            self.0 = DoubleDouble::ZERO;
+        }
+    }
+
+    impl One for DdNum {
+        fn one() -> Self {
+            DdNum(DoubleDouble::ONE)
+        }
+
+        fn is_one(&self) -> bool
+        where
+            Self: PartialEq,
+        {
+            self.0 == DoubleDouble::ONE
+        }
+
+        fn set_one(&mut self) {
+            self.0 = DoubleDouble::ONE;
+        }
+    }
+
+    impl<'a> MulDivAssign<&'a Self, &'a Self> for DdNum {
+        fn mul_div_assign(&mut self, mul: &'a Self, div: &'a Self) {
+            self.0 *= &(mul.0 / div.0)
+        }
+    }
+
+    impl Float for DdNum {
+        const BACKEND: Backend = Backend::DoubleDouble;
+
+        fn pow(&self, exp: u32) -> Self {
+            DdNum(self.0.powi(exp))
+        }
+
+        fn log2(&self) -> f64 {
+            self.0.log2()
+        }
+
+        fn is_nan(&self) -> bool {
+            self.0.is_nan()
+        }
+
+        fn from_bigint(value: &BigInt) -> Self {
+            let (top, shift) = leading_bits(value);
+            let magnitude = DoubleDouble::from_scaled(top, shift);
+            DdNum(if value.sign() == Sign::Minus {
+                -magnitude
+            } else {
+                magnitude
+            })
+        }
+    }
+
+    /// Serialized as the exact triple `(hi, lo, exp)` of [`DoubleDouble::to_parts`], with
+    /// infinities and NaN as strings.
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for DdNum {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let (hi, lo, exp) = self.0.to_parts();
+            serde::Serialize::serialize(&(JsonF64(hi), JsonF64(lo), exp), serializer)
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for DdNum {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (JsonF64(hi), JsonF64(lo), exp) = serde::Deserialize::deserialize(deserializer)?;
+            Ok(DdNum(DoubleDouble::from_parts(hi, lo, exp)))
+        }
     }
 }
@@ -1,7 +1,43 @@
+pub use num_bigint::BigInt;
+#[cfg(any(
+    feature = "inexact",
+    feature = "nightly-float",
+    feature = "double-double"
+))]
+use num_traits::ToPrimitive;
 use num_traits::{One, Zero};
 use std::iter::Sum;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
+/// Implement the operators taking a reference on the right-hand side, and the corresponding
+/// assignment operators, for a [`Copy`] type implementing the operators by value.
+#[cfg(any(feature = "inexact", feature = "double-double"))]
+macro_rules! forward_ref_ops {
+    ($type:ty; $($op:ident::$method:ident, $assign:ident::$assign_method:ident;)*) => {
+        $(
+            impl<'a> $op<&'a $type> for $type {
+                type Output = Self;
+
+                #[inline]
+                fn $method(self, rhs: &'a $type) -> Self {
+                    $op::$method(self, *rhs)
+                }
+            }
+
+            impl<'a> $assign<&'a $type> for $type {
+                #[inline]
+                fn $assign_method(&mut self, rhs: &'a $type) {
+                    *self = $op::$method(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "double-double")]
+mod dd;
+#[cfg(feature = "inexact")]
+mod ext;
 mod floats;
 
 /// The multiply and divide assignment operator.
@@ -39,6 +75,9 @@ where
         + From<i64>
         + Send,
 {
+    /// Backend of this implementation.
+    const BACKEND: Backend;
+
     /// Returns `self` to the power `exp`.
     fn pow(&self, exp: u32) -> Self;
 
@@ -47,13 +86,434 @@ where
 
     /// Returns [`true`] if `self` is not a number.
     fn is_nan(&self) -> bool;
+
+    /// Converts an arbitrary precision integer, exactly whenever it fits in the precision of
+    /// `Self`.
+    ///
+    /// Unlike `From<i64>`, the value is not limited to 64 bits, so that exact integer sums (e.g.
+    /// of binomial coefficients) can be converted once at the end.
+    fn from_bigint(value: &BigInt) -> Self;
+}
+
+/// Returns `(top, shift)` such that `top * 2^shift` approximates `|value|` with its leading 126
+/// bits, for the backends built on primitive floats.
+///
+/// If any of the discarded bits is set, the lowest bit of `top` is set as well (rounding to odd),
+/// so that a further rounding of `top` to at most 124 bits is correctly rounded.
+#[cfg(any(
+    feature = "inexact",
+    feature = "nightly-float",
+    feature = "double-double"
+))]
+pub(crate) fn leading_bits(value: &BigInt) -> (u128, i64) {
+    const BITS: u64 = 126;
+
+    let magnitude = value.magnitude();
+    let bits = magnitude.bits();
+    if bits <= BITS {
+        return (magnitude.to_u128().unwrap(), 0);
+    }
+
+    let shift = bits - BITS;
+    let top = (magnitude >> shift).to_u128().unwrap();
+    let sticky = magnitude.trailing_zeros().unwrap() < shift;
+    (top | sticky as u128, shift as i64)
 }
 
 #[cfg(feature = "dashu")]
 pub use floats::dashu::DashuNum;
+#[cfg(feature = "double-double")]
+pub use floats::double_double::DdNum;
+#[cfg(feature = "inexact")]
+pub use floats::extended::ExtF64Num;
 #[cfg(feature = "nightly-float")]
 pub use floats::f128::F128Num;
 #[cfg(feature = "inexact")]
 pub use floats::inexact::F64Num;
 #[cfg(feature = "rug")]
 pub use floats::rug::RugNum;
+
+/// Implementations of [`Float`] enabled by the crate features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Backend {
+    /// [`F64Num`], enabled by the **inexact** feature.
+    #[cfg(feature = "inexact")]
+    F64,
+    /// [`ExtF64Num`], enabled by the **inexact** feature.
+    #[cfg(feature = "inexact")]
+    ExtF64,
+    /// [`RugNum`], enabled by the **rug** feature.
+    #[cfg(feature = "rug")]
+    Rug,
+    /// [`DashuNum`], enabled by the **dashu** feature.
+    #[cfg(feature = "dashu")]
+    Dashu,
+    /// [`F128Num`], enabled by the **nightly-float** feature.
+    #[cfg(feature = "nightly-float")]
+    F128,
+    /// [`DdNum`], enabled by the **double-double** feature.
+    #[cfg(feature = "double-double")]
+    #[cfg_attr(feature = "serde", serde(rename = "dd"))]
+    DoubleDouble,
+}
+
+impl Backend {
+    /// All the enabled backends.
+    pub const ENABLED: &'static [Backend] = &[
+        #[cfg(feature = "inexact")]
+        Backend::F64,
+        #[cfg(feature = "inexact")]
+        Backend::ExtF64,
+        #[cfg(feature = "rug")]
+        Backend::Rug,
+        #[cfg(feature = "dashu")]
+        Backend::Dashu,
+        #[cfg(feature = "nightly-float")]
+        Backend::F128,
+        #[cfg(feature = "double-double")]
+        Backend::DoubleDouble,
+    ];
+
+    /// Short lowercase name of the backend.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "inexact")]
+            Backend::F64 => "f64",
+            #[cfg(feature = "inexact")]
+            Backend::ExtF64 => "ext-f64",
+            #[cfg(feature = "rug")]
+            Backend::Rug => "rug",
+            #[cfg(feature = "dashu")]
+            Backend::Dashu => "dashu",
+            #[cfg(feature = "nightly-float")]
+            Backend::F128 => "f128",
+            #[cfg(feature = "double-double")]
+            Backend::DoubleDouble => "dd",
+        }
+    }
+
+    /// Number of significant bits of the float representation.
+    pub fn precision(&self) -> u32 {
+        match *self {
+            #[cfg(feature = "inexact")]
+            Backend::F64 => f64::MANTISSA_DIGITS,
+            #[cfg(feature = "inexact")]
+            Backend::ExtF64 => f64::MANTISSA_DIGITS,
+            #[cfg(feature = "rug")]
+            Backend::Rug => RugNum::PRECISION,
+            #[cfg(feature = "dashu")]
+            Backend::Dashu => DashuNum::PRECISION as u32,
+            #[cfg(feature = "nightly-float")]
+            Backend::F128 => f128::MANTISSA_DIGITS,
+            #[cfg(feature = "double-double")]
+            Backend::DoubleDouble => 2 * f64::MANTISSA_DIGITS,
+        }
+    }
+
+    /// The enabled backend with the highest precision, if any.
+    ///
+    /// Ties are broken in favour of the backend listed last in [`Backend::ENABLED`], so that
+    /// [`ExtF64Num`] is preferred to [`F64Num`].
+    pub fn most_precise() -> Option<Backend> {
+        Backend::ENABLED
+            .iter()
+            .copied()
+            .max_by_key(Backend::precision)
+    }
+}
+
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Evaluate `$body` with the type alias `$T` bound to the [`Float`] implementation of `$backend`.
+///
+/// The arms are those of the backends enabled in this crate, whatever the features of the calling
+/// crate, so that the match is always exhaustive.
+///
+/// ```
+/// use revise_cross_parameters::float::{Backend, Float};
+/// use revise_cross_parameters::{prob_beta, with_backend};
+///
+/// for &backend in Backend::ENABLED {
+///     let prob = with_backend!(backend, T => prob_beta::<T>(24, 4, 13).log2());
+///     assert!(prob < 0.0);
+/// }
+/// ```
+#[macro_export]
+macro_rules! with_backend {
+    ($backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_inexact!([] $backend, $T => $body)
+    };
+}
+
+// Each of the following macros appends the arms of its backends if they are enabled in this crate,
+// and passes them on to the next one, the last one emitting the match.
+
+#[cfg(feature = "inexact")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_inexact {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_rug!([
+            $($arms)*
+            $crate::float::Backend::F64 => {
+                type $T = $crate::float::F64Num;
+                $body
+            }
+            $crate::float::Backend::ExtF64 => {
+                type $T = $crate::float::ExtF64Num;
+                $body
+            }
+        ] $backend, $T => $body)
+    };
+}
+
+#[cfg(not(feature = "inexact"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_inexact {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_rug!([$($arms)*] $backend, $T => $body)
+    };
+}
+
+#[cfg(feature = "rug")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_rug {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_dashu!([
+            $($arms)*
+            $crate::float::Backend::Rug => {
+                type $T = $crate::float::RugNum;
+                $body
+            }
+        ] $backend, $T => $body)
+    };
+}
+
+#[cfg(not(feature = "rug"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_rug {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_dashu!([$($arms)*] $backend, $T => $body)
+    };
+}
+
+#[cfg(feature = "dashu")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_dashu {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_f128!([
+            $($arms)*
+            $crate::float::Backend::Dashu => {
+                type $T = $crate::float::DashuNum;
+                $body
+            }
+        ] $backend, $T => $body)
+    };
+}
+
+#[cfg(not(feature = "dashu"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_dashu {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_f128!([$($arms)*] $backend, $T => $body)
+    };
+}
+
+#[cfg(feature = "nightly-float")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_f128 {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_dd!([
+            $($arms)*
+            $crate::float::Backend::F128 => {
+                type $T = $crate::float::F128Num;
+                $body
+            }
+        ] $backend, $T => $body)
+    };
+}
+
+#[cfg(not(feature = "nightly-float"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_f128 {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        $crate::__with_backend_dd!([$($arms)*] $backend, $T => $body)
+    };
+}
+
+#[cfg(feature = "double-double")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_dd {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        match $backend {
+            $($arms)*
+            $crate::float::Backend::DoubleDouble => {
+                type $T = $crate::float::DdNum;
+                $body
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "double-double"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_backend_dd {
+    ([$($arms:tt)*] $backend:expr, $T:ident => $body:expr) => {
+        match $backend {
+            $($arms)*
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    macro_rules! float_test {
+        ($name:ident: $type:ty, $backend:expr) => {
+            mod $name {
+                use crate::float::{BigInt, Float};
+                use num_traits::{One, Zero};
+
+                #[test]
+                fn test_from_i64() {
+                    // 2^(p-1) + 1 is the largest odd integer representable with p bits
+                    let precision = $backend.precision().min(63);
+                    let x = 1i64 << (precision - 1);
+                    let diff = <$type>::from(x + 1) - <$type>::from(x);
+                    assert!(diff == <$type>::one());
+                }
+
+                #[test]
+                fn test_zero_one() {
+                    // zero and one carry the precision of the backend, so that the results of
+                    // operations on them can be rounded, e.g. binom(n, n) = 1 in log2
+                    assert_eq!(<$type>::one().log2(), 0.0);
+                    let third = <$type>::one() / <$type>::from(3);
+                    assert!((third.log2() + 3f64.log2()).abs() < 1e-9);
+                    let third = (<$type>::zero() + <$type>::one()) / <$type>::from(3);
+                    assert!((third.log2() + 3f64.log2()).abs() < 1e-9);
+                }
+
+                #[test]
+                fn test_from_bigint() {
+                    let precision = $backend.precision() as usize;
+                    let x = (BigInt::one() << (precision - 1)) + 1;
+                    let diff = <$type>::from_bigint(&x) - <$type>::from_bigint(&(&x - 1));
+                    assert!(diff == <$type>::one());
+
+                    // far beyond 64 bits, and beyond the exponent range of f64 for the backends
+                    // extending it
+                    let scale = if $backend.name() == "f64" {
+                        900 - precision
+                    } else {
+                        2000
+                    };
+                    let big = <$type>::from_bigint(&(&x << scale));
+                    let diff = big - <$type>::from_bigint(&((&x - 1) << scale));
+                    assert!(diff == <$type>::from_bigint(&(BigInt::one() << scale)));
+                    assert!(<$type>::from_bigint(&-(&x << scale)) < <$type>::from(0));
+                    assert!(
+                        <$type>::from_bigint(&-&x) == <$type>::from(0) - <$type>::from_bigint(&x)
+                    );
+                }
+
+                #[cfg(feature = "serde")]
+                #[test]
+                fn test_serde() {
+                    let scale = if $backend.name() == "f64" { 500 } else { 2000 };
+                    let big = <$type>::from_bigint(&(BigInt::one() << scale));
+
+                    for value in [
+                        <$type>::from(0),
+                        <$type>::from(-7),
+                        <$type>::one() / <$type>::from(3),
+                        <$type>::one() / &big,
+                        big / <$type>::from(3),
+                    ] {
+                        let json = serde_json::to_string(&value).unwrap();
+                        let parsed: $type = serde_json::from_str(&json).unwrap();
+                        assert!(parsed == value, "{json}");
+                    }
+                }
+            }
+        };
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_backend_serde() {
+        for &backend in crate::float::Backend::ENABLED {
+            let json = serde_json::to_string(&backend).unwrap();
+            assert_eq!(json, format!("\"{}\"", backend.name()));
+            assert_eq!(
+                serde_json::from_str::<crate::float::Backend>(&json).unwrap(),
+                backend
+            );
+        }
+    }
+
+    #[cfg(all(feature = "serde", feature = "inexact"))]
+    #[test]
+    fn test_serde_non_finite() {
+        use crate::float::{ExtF64Num, F64Num, Float};
+
+        let json = serde_json::to_string(&F64Num::from(1)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<F64Num>(&json).unwrap(),
+            F64Num::from(1)
+        );
+
+        for (value, expected) in [(1, "\"inf\""), (-1, "\"-inf\"")] {
+            let infinite = F64Num::from(value) / F64Num::from(0);
+            let json = serde_json::to_string(&infinite).unwrap();
+            assert_eq!(json, expected);
+            assert_eq!(serde_json::from_str::<F64Num>(&json).unwrap(), infinite);
+
+            let infinite = ExtF64Num::from(value) / ExtF64Num::from(0);
+            let json = serde_json::to_string(&infinite).unwrap();
+            assert_eq!(json, format!("[{expected},0]"));
+            assert_eq!(serde_json::from_str::<ExtF64Num>(&json).unwrap(), infinite);
+        }
+
+        let nan = F64Num::from(0) / F64Num::from(0);
+        let json = serde_json::to_string(&nan).unwrap();
+        assert_eq!(json, "\"NaN\"");
+        assert!(serde_json::from_str::<F64Num>(&json).unwrap().is_nan());
+        assert!(serde_json::from_str::<F64Num>("\"one\"").is_err());
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty, $backend:expr,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type, $backend }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num, crate::float::Backend::F64,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num, crate::float::Backend::ExtF64,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum, crate::float::Backend::Rug,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum, crate::float::Backend::Dashu,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num, crate::float::Backend::F128,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum, crate::float::Backend::DoubleDouble,
+    }
+}
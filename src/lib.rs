@@ -2,7 +2,9 @@
 //! parameters, based on the results of [`BLPST25`](https://eprint.iacr.org/2025/127).
 //!
 //! It provides methods for estimating the cost of a forgery to the scheme
-//! employing the fixed-weight optimisation.
+//! employing the fixed-weight optimisation. The same estimates are available
+//! for other schemes with a fixed-weight second challenge through the
+//! [`scheme`] module.
 //!
 //! # Feature flags
 //!
@@ -12,17 +14,42 @@
 //! - **inexact**: use primitive [`f64`] for [`Float`] implementation
 //! - **rug**: use [`rug::Float`](https://docs.rs/rug/latest/rug/struct.Float.html) for [`Float`] implementation
 //! - **dashu**: use [`dashu_float::FBig`](https://docs.rs/dashu-float/latest/dashu_float/struct.FBig.html) for [`Float`] implementation
+//! - **double-double**: use a pure-Rust double-double float for [`Float`] implementation, with no C dependencies
+//! - **serde**: implement `Serialize` and `Deserialize` for the [`Float`] implementations and the results, such as [`Estimate`], without loss of precision (with `serde_json`, this requires its `float_roundtrip` feature for the backends based on [`f64`])
 //! - **nightly-float**: enable unstable [`f128`] float type for [`Float`] implementation
 
 #![cfg_attr(feature = "nightly-float", feature(f128))]
-use float::Float;
-use indicatif::{ParallelProgressIterator, ProgressStyle};
-use rayon::prelude::*;
-use std::cmp::{max, min};
-
+use float::{BigInt, Float};
+use indicatif::ProgressStyle;
+use num_traits::One;
+use provenance::Provenance;
+use scheme::Scheme;
+use std::cmp::min;
+use std::time::Instant;
+
+pub mod bound;
+pub mod checkpoint;
+pub mod composition;
+pub mod explain;
 pub mod float;
-
-fn get_default_pb_style(quiet: bool) -> ProgressStyle {
+pub mod presets;
+pub mod provenance;
+pub mod scheme;
+#[cfg(feature = "simulate")]
+pub mod simulate;
+pub mod sweep;
+#[cfg(feature = "simulate")]
+pub mod toy;
+pub mod verify;
+
+/// Version of the crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Version of the estimators, bumped with every change of the library that changes the estimates,
+/// so that the estimates cached or checkpointed by a previous version are discarded.
+pub const ESTIMATOR_VERSION: u32 = 1;
+
+pub(crate) fn get_default_pb_style(quiet: bool) -> ProgressStyle {
     match quiet {
         false => ProgressStyle::default_bar()
             .template("[{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len}")
@@ -58,48 +85,61 @@ pub fn binom<T: Float>(n: i64, k: i64) -> T {
     r
 }
 
+/// Exact binomial coefficients `binom(n, k)` for `n <= t`, built once with Pascal's rule.
+pub(crate) struct Binomials(Vec<Vec<BigInt>>);
+
+impl Binomials {
+    pub(crate) fn new(t: i64) -> Self {
+        let mut rows: Vec<Vec<BigInt>> = Vec::with_capacity(t as usize + 1);
+        for n in 0..=t as usize {
+            let mut row = Vec::with_capacity(n + 1);
+            row.push(BigInt::one());
+            for k in 1..n {
+                row.push(&rows[n - 1][k - 1] + &rows[n - 1][k]);
+            }
+            if n > 0 {
+                row.push(BigInt::one());
+            }
+            rows.push(row);
+        }
+        Binomials(rows)
+    }
+
+    /// Returns `binom(n, k)`, which is zero if `k < 0` or `k > n`.
+    pub(crate) fn get(&self, n: i64, k: i64) -> &BigInt {
+        if k < 0 || k > n {
+            &BigInt::ZERO
+        } else {
+            &self.0[n as usize][k as usize]
+        }
+    }
+}
+
 #[doc(hidden)]
 #[inline]
 pub fn prob_beta<T: Float>(t: i64, ts: i64, p: i64) -> T {
-    let p_minus_one = T::from(p - 1);
-    let inv_p_minus_one = T::one() / p_minus_one;
-    let one_minus_inv_p_minus_one = T::one() - &inv_p_minus_one;
-
-    (ts..=t)
-        .map(|j| {
-            binom::<T>(t, j)
-                * inv_p_minus_one.pow(j as u32)
-                * one_minus_inv_p_minus_one.pow((t - j) as u32)
-        })
-        .sum()
+    scheme::prob_beta(&Scheme::cross(p, t, 0), ts)
 }
 
 #[doc(hidden)]
 #[inline]
 pub fn prob_b<T: Float>(t: i64, ts: i64, w: i64, p: i64) -> T {
-    let p_minus_one = T::from(p - 1);
-    let inv_p_minus_one = T::one() / p_minus_one;
-    let one_minus_inv_p_minus_one = T::one() - &inv_p_minus_one;
-    let binom_t_w_squared = binom::<T>(t, w).pow(2);
-
-    let sum: T = (ts..=t)
-        .map(|j| {
-            binom::<T>(t, j)
-                * inv_p_minus_one.pow(j as u32)
-                * one_minus_inv_p_minus_one.pow((t - j) as u32)
-                * (max(0, j - (t - w))..=min(j, w))
-                    .map(|ws| binom::<T>(j, ws).pow(2) * binom::<T>(t - j, w - ws))
-                    .sum::<T>()
-                / &binom_t_w_squared
-        })
-        .sum();
-
-    let result = sum / prob_beta::<T>(t, ts, p);
-    if result.is_nan() {
-        // TODO: warn of NaN
-        T::zero()
+    scheme::prob_b(&Scheme::cross(p, t, w), ts)
+}
+
+/// Checks the CROSS parameters, which would otherwise make the estimators panic, returning the
+/// reason they are invalid otherwise.
+///
+/// # Arguments
+///
+/// * `t` - Number of parallel iterations, at most 65535.
+/// * `w` - Fixed-weight parameter, between 0 and `t`.
+/// * `p` - Characteristic of the finite field, at least 3.
+pub fn check_params(t: i64, w: i64, p: i64) -> Result<(), String> {
+    if p < 3 {
+        Err("p must be at least 3".to_string())
     } else {
-        result
+        scheme::Scheme::cross(p, t, w).check()
     }
 }
 
@@ -115,64 +155,19 @@ pub fn prob_b<T: Float>(t: i64, ts: i64, w: i64, p: i64) -> T {
 /// * `quiet` - If [`true`] hide the progress bar.
 #[inline]
 pub fn estimate_attack<T: Float>(t: i64, w: i64, p: i64, quiet: bool) -> (i64, f64) {
-    let result = (0..=u16::try_from(t).unwrap())
-        .into_par_iter()
-        .progress_with_style(get_default_pb_style(quiet))
-        .map(|ts| {
-            let beta_prob = prob_beta::<T>(t, ts as i64, p);
-            let b_prob = prob_b::<T>(t, ts as i64, w, p);
-
-            let comp: T = T::one() / beta_prob + T::one() / b_prob;
-
-            (comp, ts)
-        })
-        .min_by(|(comp_a, _), (comp_b, _)| comp_a.partial_cmp(comp_b).unwrap())
-        .unwrap();
-
-    let complog = result.0.log2();
-    let ts = result.1;
-
-    (ts as i64, complog)
+    scheme::estimate_attack::<T>(&Scheme::cross(p, t, w), quiet)
 }
 
 #[doc(hidden)]
 #[inline]
 pub fn prob_b_new<T: Float>(t: i64, ts: i64, w: i64, p: i64) -> (i64, T) {
-    let p_minus_one = T::from(p - 1);
-    let inv_p_minus_one = T::one() / p_minus_one;
-    let one_minus_inv_p_minus_one = T::one() - &inv_p_minus_one;
-    let beta_prob = prob_beta::<T>(t, ts, p);
-    let binom_tw = binom::<T>(t, w);
-
-    let result = (w..=t)
-        .map(|aa| {
-            let prob = (ts..=t)
-                .map(|j| {
-                    binom::<T>(t, j)
-                        * inv_p_minus_one.pow(j as u32)
-                        * one_minus_inv_p_minus_one.pow((t - j) as u32)
-                        * (max(0, aa - j)..=min(t - j, w))
-                            .map(|ws| {
-                                binom::<T>(t - j, ws)
-                                    * binom::<T>(j, aa - ws)
-                                    * binom::<T>(j, w - ws)
-                            })
-                            .sum::<T>()
-                })
-                .sum::<T>()
-                / binom::<T>(t, aa);
-            (aa, prob)
-        })
-        .max_by(|(_, comp_a), (_, comp_b)| comp_a.partial_cmp(comp_b).unwrap())
-        .unwrap();
-
-    let prob = result.1 / (beta_prob * binom_tw);
-    if prob.is_nan() {
-        // TODO: warn of NaN
-        (result.0, T::zero())
-    } else {
-        (result.0, prob)
-    }
+    scheme::prob_b_new(&Scheme::cross(p, t, w), ts)
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn prob_b_new_alpha<T: Float>(t: i64, ts: i64, w: i64, p: i64, aa: i64) -> T {
+    scheme::prob_b_new_alpha(&Scheme::cross(p, t, w), ts, aa)
 }
 
 /// Estimate cost of new attack as described in [`BLPST25`](https://eprint.iacr.org/2025/127).
@@ -187,33 +182,155 @@ pub fn prob_b_new<T: Float>(t: i64, ts: i64, w: i64, p: i64) -> (i64, T) {
 /// * `quiet` - If [`true`] hide the progress bar.
 #[inline]
 pub fn estimate_attack_new<T: Float>(t: i64, w: i64, p: i64, quiet: bool) -> (i64, i64, f64) {
-    let result = (0..=u16::try_from(t).unwrap())
-        .into_par_iter()
-        .progress_with_style(get_default_pb_style(quiet))
-        .map(|ts| {
-            let beta_prob = prob_beta::<T>(t, ts as i64, p);
-            let (aa, b_prob) = prob_b_new::<T>(t, ts as i64, w, p);
+    scheme::estimate_attack_new::<T>(&Scheme::cross(p, t, w), quiet)
+}
 
-            let comp = T::one() / beta_prob + T::one() / b_prob;
+/// Optimum of an estimate, as returned by [`estimate_attack`] and [`estimate_attack_new`], or
+/// by [`Estimate::attack`] and [`Estimate::attack_new`] together with its provenance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Estimate {
+    /// Optimal number of rounds `t*` guessed in the first phase.
+    pub ts: i64,
+    /// Optimal number `alpha` of non-zero second challenges guessed by the new attack, or
+    /// [`None`] for the original attack.
+    pub alpha: Option<i64>,
+    /// Cost as log2 of the estimated gate count.
+    pub cost: f64,
+    /// How and by which build the estimate was computed, if known.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub provenance: Option<Provenance>,
+}
 
-            (comp, ts, aa)
-        })
-        .min_by(|(comp_a, _, _), (comp_b, _, _)| comp_a.partial_cmp(comp_b).unwrap())
-        .unwrap();
+impl Estimate {
+    /// Estimate cost of original attack on `scheme` as [`scheme::estimate_attack`], recording
+    /// its [`Provenance`].
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - Parameters of the scheme, see [`Scheme::cross`] for CROSS.
+    /// * `quiet` - If [`true`] hide the progress bar.
+    pub fn attack<T: Float>(scheme: &Scheme, quiet: bool) -> Self {
+        let start = Instant::now();
+        let (ts, cost) = scheme::estimate_attack::<T>(scheme, quiet);
+        Estimate {
+            ts,
+            alpha: None,
+            cost,
+            provenance: Some(Provenance::new(
+                T::BACKEND,
+                0..=scheme.t,
+                None,
+                start.elapsed(),
+            )),
+        }
+    }
 
-    let complog = result.0.log2();
-    let ts = result.1;
-    let aa = result.2;
+    /// Estimate cost of new attack on `scheme` as [`scheme::estimate_attack_new`], recording its
+    /// [`Provenance`].
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - Parameters of the scheme, see [`Scheme::cross`] for CROSS.
+    /// * `quiet` - If [`true`] hide the progress bar.
+    pub fn attack_new<T: Float>(scheme: &Scheme, quiet: bool) -> Self {
+        let start = Instant::now();
+        let (ts, alpha, cost) = scheme::estimate_attack_new::<T>(scheme, quiet);
+        Estimate {
+            ts,
+            alpha: Some(alpha),
+            cost,
+            provenance: Some(Provenance::new(
+                T::BACKEND,
+                0..=scheme.t,
+                Some(scheme.w..=scheme.t),
+                start.elapsed(),
+            )),
+        }
+    }
+}
 
-    (ts as i64, aa, complog)
+impl From<(i64, f64)> for Estimate {
+    fn from((ts, cost): (i64, f64)) -> Self {
+        Estimate {
+            ts,
+            alpha: None,
+            cost,
+            provenance: None,
+        }
+    }
+}
+
+impl From<(i64, i64, f64)> for Estimate {
+    fn from((ts, alpha, cost): (i64, i64, f64)) -> Self {
+        Estimate {
+            ts,
+            alpha: Some(alpha),
+            cost,
+            provenance: None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn test_check_params() {
+        assert_eq!(crate::check_params(252, 212, 127), Ok(()));
+        assert_eq!(crate::check_params(0, 0, 3), Ok(()));
+        assert!(crate::check_params(252, 212, 2).is_err());
+        assert!(crate::check_params(65536, 212, 127).is_err());
+        assert!(crate::check_params(-1, 0, 127).is_err());
+        assert!(crate::check_params(252, 253, 127).is_err());
+        assert!(crate::check_params(252, -1, 127).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_estimate_serde() {
+        let estimate = crate::Estimate::from((38, 227, 120.46128740692139));
+        let json = serde_json::to_string(&estimate).unwrap();
+        assert_eq!(json, r#"{"ts":38,"alpha":227,"cost":120.46128740692139}"#);
+        assert_eq!(
+            serde_json::from_str::<crate::Estimate>(&json).unwrap(),
+            estimate
+        );
+
+        let estimate = crate::Estimate::from((40, 128.01));
+        let json = serde_json::to_string(&estimate).unwrap();
+        assert_eq!(
+            serde_json::from_str::<crate::Estimate>(&json).unwrap(),
+            estimate
+        );
+
+        let estimate = crate::Estimate {
+            provenance: Some(crate::provenance::Provenance {
+                version: "0.1.0".to_string(),
+                commit: None,
+                backend: "f1024".to_string(),
+                precision: 1024,
+                threads: 4,
+                ts_range: 0..=163,
+                alpha_range: None,
+                seconds: 0.5,
+            }),
+            ..estimate
+        };
+        let json = serde_json::to_string(&estimate).unwrap();
+        assert_eq!(
+            serde_json::from_str::<crate::Estimate>(&json).unwrap(),
+            estimate
+        );
+    }
+
     macro_rules! float_test {
         ($name:ident: $type:ty) => {
             mod $name {
-                use crate::{float::Float, prob_b, prob_b_new, prob_beta};
+                use crate::scheme::{self, Scheme};
+                use crate::{float::Float, prob_b, prob_b_new, prob_beta, Estimate, VERSION};
 
                 #[test]
                 fn test_prob_beta() {
@@ -241,6 +358,37 @@ mod tests {
 
                     assert_eq!(-prob.log2().round(), 120.0);
                 }
+
+                #[test]
+                fn test_estimate_provenance() {
+                    let scheme = Scheme::cross(13, 16, 8);
+
+                    let estimate = Estimate::attack::<$type>(&scheme, true);
+                    let provenance = estimate.provenance.clone().unwrap();
+                    assert_eq!(
+                        estimate,
+                        Estimate {
+                            provenance: Some(provenance.clone()),
+                            ..scheme::estimate_attack::<$type>(&scheme, true).into()
+                        }
+                    );
+                    assert_eq!(provenance.version, VERSION);
+                    assert_eq!(provenance.backend, <$type>::BACKEND.name());
+                    assert_eq!(provenance.precision, <$type>::BACKEND.precision());
+                    assert_eq!(provenance.ts_range, 0..=16);
+                    assert_eq!(provenance.alpha_range, None);
+
+                    let estimate = Estimate::attack_new::<$type>(&scheme, true);
+                    let provenance = estimate.provenance.clone().unwrap();
+                    assert_eq!(
+                        estimate,
+                        Estimate {
+                            provenance: Some(provenance.clone()),
+                            ..scheme::estimate_attack_new::<$type>(&scheme, true).into()
+                        }
+                    );
+                    assert_eq!(provenance.alpha_range, Some(8..=16));
+                }
             }
         };
     }
@@ -262,8 +410,10 @@ mod tests {
 
     tests! {
         #[cfg(feature = "inexact")] f64: crate::float::F64Num,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num,
         #[cfg(feature = "rug")] rug: crate::float::RugNum,
         #[cfg(feature = "dashu")] dashu: crate::float::DashuNum,
         #[cfg(feature = "nightly-float")] f128: crate::float::F128Num,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum,
     }
 }
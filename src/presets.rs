@@ -0,0 +1,97 @@
+//! Parameter sets of the CROSS R-SDP and R-SDP(G) variants, as listed in the results of the
+//! README.
+//!
+//! The presets are named after the variant, the NIST security category and the optimisation
+//! target, e.g. `CROSS-R-SDP-1-fast` or `CROSS-R-SDP-G-5-small`.
+
+/// Named CROSS parameter set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preset {
+    /// Name of the parameter set.
+    pub name: &'static str,
+    /// Prime order of the finite field.
+    pub p: i64,
+    /// Number of parallel repetitions.
+    pub t: i64,
+    /// Fixed-weight parameter for the second challenge.
+    pub w: i64,
+    /// NIST security category, 1, 3 or 5.
+    pub category: u8,
+}
+
+impl Preset {
+    /// Security threshold of the category of the parameter set in bits.
+    pub fn threshold(&self) -> f64 {
+        threshold(self.category).unwrap()
+    }
+}
+
+const fn preset(name: &'static str, p: i64, t: i64, w: i64, category: u8) -> Preset {
+    Preset {
+        name,
+        p,
+        t,
+        w,
+        category,
+    }
+}
+
+/// Every CROSS parameter set.
+pub const PRESETS: &[Preset] = &[
+    preset("CROSS-R-SDP-1-fast", 127, 163, 85, 1),
+    preset("CROSS-R-SDP-1-balanced", 127, 252, 212, 1),
+    preset("CROSS-R-SDP-1-small", 127, 960, 938, 1),
+    preset("CROSS-R-SDP-3-fast", 127, 245, 127, 3),
+    preset("CROSS-R-SDP-3-balanced", 127, 398, 340, 3),
+    preset("CROSS-R-SDP-3-small", 127, 945, 907, 3),
+    preset("CROSS-R-SDP-5-fast", 127, 327, 169, 5),
+    preset("CROSS-R-SDP-5-balanced", 127, 507, 427, 5),
+    preset("CROSS-R-SDP-5-small", 127, 968, 912, 5),
+    preset("CROSS-R-SDP-G-1-fast", 509, 153, 79, 1),
+    preset("CROSS-R-SDP-G-1-balanced", 509, 243, 206, 1),
+    preset("CROSS-R-SDP-G-1-small", 509, 871, 850, 1),
+    preset("CROSS-R-SDP-G-3-fast", 509, 230, 123, 3),
+    preset("CROSS-R-SDP-G-3-balanced", 509, 255, 176, 3),
+    preset("CROSS-R-SDP-G-3-small", 509, 949, 914, 3),
+    preset("CROSS-R-SDP-G-5-fast", 509, 306, 157, 5),
+    preset("CROSS-R-SDP-G-5-balanced", 509, 356, 257, 5),
+    preset("CROSS-R-SDP-G-5-small", 509, 996, 945, 5),
+];
+
+/// Security threshold in bits of the NIST security `category`, or [`None`] if it is not 1, 3 or
+/// 5.
+pub fn threshold(category: u8) -> Option<f64> {
+    match category {
+        1 => Some(128.0),
+        3 => Some(192.0),
+        5 => Some(256.0),
+        _ => None,
+    }
+}
+
+/// Preset named `name`, ignoring the case.
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS
+        .iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, threshold, PRESETS};
+
+    #[test]
+    fn test_find() {
+        let preset = find("cross-r-sdp-g-3-balanced").unwrap();
+        assert_eq!((preset.p, preset.t, preset.w), (509, 255, 176));
+        assert!(find("CROSS-R-SDP-7-fast").is_none());
+
+        for preset in PRESETS {
+            assert_eq!(find(preset.name), Some(preset));
+            let category = format!("-{}-", preset.category);
+            assert!(preset.name.contains(&category), "{}", preset.name);
+        }
+        assert_eq!(preset.threshold(), 192.0);
+        assert_eq!(threshold(2), None);
+    }
+}
@@ -0,0 +1,157 @@
+//! Provenance of the estimates, so that tables and exports of the results can be traced back to
+//! the build and the settings which produced them.
+
+use crate::float::Backend;
+use crate::VERSION;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// Git commit the crate was built from, with a `-dirty` suffix if the files of the crate had
+/// changes, or [`None`] if it was not built at the top of a git checkout.
+pub const COMMIT: Option<&str> = option_env!("REVISE_CROSS_PARAMETERS_COMMIT");
+
+/// Describes the build of the crate with `version` and `commit`, as in the tables of the
+/// estimates.
+pub fn describe_build(version: &str, commit: Option<&str>) -> String {
+    match commit {
+        Some(commit) => format!("revise_cross_parameters {version} (commit {commit})"),
+        None => format!("revise_cross_parameters {version}"),
+    }
+}
+
+/// Describes this build of the crate, such as `revise_cross_parameters 0.1.0 (commit
+/// 0123456789ab)`.
+pub fn build() -> String {
+    describe_build(VERSION, COMMIT)
+}
+
+/// Describes the number of `threads` an estimate ran on, such as `1 thread` or `8 threads`.
+pub fn describe_threads(threads: usize) -> String {
+    match threads {
+        1 => "1 thread".to_string(),
+        _ => format!("{threads} threads"),
+    }
+}
+
+/// How and by which build an [`Estimate`](crate::Estimate) was computed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Provenance {
+    /// Version of the crate, see [`VERSION`].
+    pub version: String,
+    /// Git commit of the crate, see [`COMMIT`].
+    pub commit: Option<String>,
+    /// Name of the backend, as given by [`Backend::name`], so that the estimates of backends
+    /// disabled in another build can still be read.
+    pub backend: String,
+    /// Precision of the backend in bits.
+    pub precision: u32,
+    /// Number of threads of the rayon thread pool.
+    pub threads: usize,
+    /// Range of `t*` searched.
+    pub ts_range: RangeInclusive<i64>,
+    /// Range of `alpha` searched by the new attack, or [`None`] for the original attack and for
+    /// the attacks guessing a composition.
+    pub alpha_range: Option<RangeInclusive<i64>>,
+    /// Wall-clock time of the estimate in seconds.
+    pub seconds: f64,
+}
+
+impl Provenance {
+    /// Provenance of an estimate computed by this build with `backend` on the current rayon
+    /// thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - Backend of the estimate.
+    /// * `ts_range` - Range of `t*` searched.
+    /// * `alpha_range` - Range of `alpha` searched, if any.
+    /// * `elapsed` - Wall-clock time of the estimate.
+    pub fn new(
+        backend: Backend,
+        ts_range: RangeInclusive<i64>,
+        alpha_range: Option<RangeInclusive<i64>>,
+        elapsed: Duration,
+    ) -> Self {
+        Provenance {
+            version: VERSION.to_string(),
+            commit: COMMIT.map(str::to_string),
+            backend: backend.name().to_string(),
+            precision: backend.precision(),
+            threads: rayon::current_num_threads(),
+            ts_range,
+            alpha_range,
+            seconds: elapsed.as_secs_f64(),
+        }
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {} backend with {} bits, {}, t* in {}..={}",
+            describe_build(&self.version, self.commit.as_deref()),
+            self.backend,
+            self.precision,
+            describe_threads(self.threads),
+            self.ts_range.start(),
+            self.ts_range.end()
+        )?;
+        if let Some(alpha_range) = &self.alpha_range {
+            write!(
+                f,
+                ", alpha in {}..={}",
+                alpha_range.start(),
+                alpha_range.end()
+            )?;
+        }
+        write!(f, ", {:.2} s", self.seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let provenance = Provenance {
+            version: "0.1.0".to_string(),
+            commit: Some("0123456789ab".to_string()),
+            backend: "dd".to_string(),
+            precision: 106,
+            threads: 8,
+            ts_range: 0..=252,
+            alpha_range: Some(212..=252),
+            seconds: 1.234,
+        };
+        assert_eq!(
+            provenance.to_string(),
+            "revise_cross_parameters 0.1.0 (commit 0123456789ab), dd backend with 106 bits, \
+             8 threads, t* in 0..=252, alpha in 212..=252, 1.23 s"
+        );
+
+        let provenance = Provenance {
+            commit: None,
+            alpha_range: None,
+            ..provenance
+        };
+        assert_eq!(
+            provenance.to_string(),
+            "revise_cross_parameters 0.1.0, dd backend with 106 bits, 8 threads, t* in 0..=252, \
+             1.23 s"
+        );
+
+        let provenance = Provenance {
+            threads: 1,
+            ..provenance
+        };
+        assert_eq!(
+            provenance.to_string(),
+            "revise_cross_parameters 0.1.0, dd backend with 106 bits, 1 thread, t* in 0..=252, \
+             1.23 s"
+        );
+    }
+}
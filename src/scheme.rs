@@ -0,0 +1,490 @@
+//! Forgery estimates for a generic Fiat–Shamir signature with a fixed-weight second challenge.
+//!
+//! The fixed-weight optimisation of the second challenge is not specific to CROSS: the same
+//! analysis applies to any five-round identification protocol made non-interactive with
+//! Fiat–Shamir, where `t` rounds are repeated in parallel and the second challenge is constrained
+//! to have exactly `w` non-zero rounds. A [`Scheme`] captures the few quantities the attack depends
+//! on, and the functions of this module estimate the forgery for any such scheme. The functions at
+//! the root of the crate are the special case returned by [`Scheme::cross`].
+//!
+//! In each round the first challenge is uniform over `first_challenges` values, while the second
+//! challenge is either zero or one of `second_challenges - 1` non-zero values. The adversary can
+//! answer every second challenge in the rounds where it guessed the first one, and a single
+//! prepared value in the others.
+
+use crate::float::{BigInt, Float};
+use crate::{binom, get_default_pb_style, Binomials};
+use indicatif::ParallelProgressIterator;
+use num_traits::Zero;
+use rayon::prelude::*;
+use std::cmp::{max, min};
+
+/// Abstract description of a signature scheme with a fixed-weight second challenge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scheme {
+    /// Number of parallel rounds.
+    pub t: i64,
+    /// Number of rounds with a non-zero second challenge.
+    pub w: i64,
+    /// Size of the first challenge space of a single round.
+    pub first_challenges: i64,
+    /// Number of values the second challenge of a single round can take, including zero.
+    pub second_challenges: i64,
+}
+
+impl Scheme {
+    /// Description of CROSS over `Fp`, with first challenges in `Fp^*` and binary second challenges.
+    pub fn cross(p: i64, t: i64, w: i64) -> Self {
+        Scheme {
+            t,
+            w,
+            first_challenges: p - 1,
+            second_challenges: 2,
+        }
+    }
+
+    /// Check that the scheme can be estimated, that is `t` is between 0 and 65535, `w` between 0
+    /// and `t` and both challenge spaces have at least two elements.
+    pub fn check(&self) -> Result<(), String> {
+        if !(0..=u16::MAX as i64).contains(&self.t) {
+            Err("t must be between 0 and 65535".to_string())
+        } else if !(0..=self.t).contains(&self.w) {
+            Err("w must be between 0 and t".to_string())
+        } else if self.first_challenges < 2 {
+            Err("the first challenge space must have at least two elements".to_string())
+        } else if self.second_challenges < 2 {
+            Err("the second challenge space must have at least two elements".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Number of values of the non-zero second challenges to the power `w`, which scales the
+    /// exact sums to integers.
+    pub(crate) fn second_scale(&self) -> BigInt {
+        BigInt::from(self.second_challenges - 1).pow(self.w as u32)
+    }
+}
+
+/// Probabilities of guessing the first challenge of a single round and of missing it.
+pub(crate) struct FirstGuess<T> {
+    hit: T,
+    miss: T,
+}
+
+impl<T: Float> FirstGuess<T> {
+    pub(crate) fn new(scheme: &Scheme) -> Self {
+        let q = T::from(scheme.first_challenges);
+        let hit = T::one() / q;
+        let miss = T::one() - &hit;
+        FirstGuess { hit, miss }
+    }
+
+    /// `count` times the probability of guessing the first challenges of `j` given rounds out of
+    /// `t` and missing the others.
+    #[inline]
+    pub(crate) fn term(&self, count: T, t: i64, j: i64) -> T {
+        count * self.hit.pow(j as u32) * self.miss.pow((t - j) as u32)
+    }
+}
+
+/// Probability that at least `ts` first challenges are guessed correctly.
+#[inline]
+pub fn prob_beta<T: Float>(scheme: &Scheme, ts: i64) -> T {
+    let t = scheme.t;
+    let guess = FirstGuess::<T>::new(scheme);
+
+    (ts..=t).map(|j| guess.term(binom::<T>(t, j), t, j)).sum()
+}
+
+/// Exact sum over `ws` of the combinations of the original attack when `j` first challenges are
+/// guessed, multiplied by `binom(t, j)` and scaled by `(second_challenges - 1)^w` so that it is
+/// an integer.
+pub(crate) fn sum_b_exact(scheme: &Scheme, binomials: &Binomials, j: i64) -> BigInt {
+    let Scheme { t, w, .. } = *scheme;
+    let m = scheme.second_challenges - 1;
+    let lo = max(0, j - (t - w));
+
+    // Horner's rule on the powers m^ws, starting from the highest
+    let mut sum = BigInt::zero();
+    for ws in (lo..=min(j, w)).rev() {
+        let a = binomials.get(j, ws);
+        sum = sum * m + a * a * binomials.get(t - j, w - ws);
+    }
+    sum *= BigInt::from(m).pow(lo as u32);
+
+    sum * binomials.get(t, j)
+}
+
+/// Term `j` of the sum of [`prob_b`] before its scaling: the probability of guessing exactly `j`
+/// first challenges and then the second challenge, times `binom(t, w)^2 (second_challenges - 1)^w`.
+#[inline]
+pub(crate) fn term_b<T: Float>(
+    scheme: &Scheme,
+    binomials: &Binomials,
+    guess: &FirstGuess<T>,
+    j: i64,
+) -> T {
+    let exact = T::from_bigint(&sum_b_exact(scheme, binomials, j));
+    guess.term(exact, scheme.t, j)
+}
+
+/// Success probability of the second phase of the original attack, given the success of the first.
+#[inline]
+pub fn prob_b<T: Float>(scheme: &Scheme, ts: i64) -> T {
+    let Scheme { t, w, .. } = *scheme;
+    let guess = FirstGuess::<T>::new(scheme);
+    let binomials = Binomials::new(t);
+    let scale = binomials.get(t, w).pow(2) * scheme.second_scale();
+    let scale = T::from_bigint(&scale);
+
+    let sum: T = (ts..=t)
+        .map(|j| term_b(scheme, &binomials, &guess, j) / &scale)
+        .sum();
+
+    let result = sum / prob_beta::<T>(scheme, ts);
+    if result.is_nan() {
+        // TODO: warn of NaN
+        T::zero()
+    } else {
+        result
+    }
+}
+
+/// Exact sum over `ws` of the combinations of the new attack guessing `aa` non-zero second
+/// challenges when `j` first challenges are guessed, multiplied by `binom(t, j)` and scaled by
+/// `(second_challenges - 1)^w` so that it is an integer.
+pub(crate) fn sum_b_new_exact(scheme: &Scheme, binomials: &Binomials, j: i64, aa: i64) -> BigInt {
+    let Scheme { t, w, .. } = *scheme;
+    let m = scheme.second_challenges - 1;
+    let hi = min(t - j, w);
+
+    // Horner's rule on the powers m^(w - ws), starting from the highest
+    let mut sum = BigInt::zero();
+    for ws in max(0, aa - j)..=hi {
+        sum = sum * m
+            + binomials.get(t - j, ws) * binomials.get(j, aa - ws) * binomials.get(j, w - ws);
+    }
+    sum *= BigInt::from(m).pow((w - hi) as u32);
+
+    sum * binomials.get(t, j)
+}
+
+/// Term `j` of the sum of [`prob_b_new_alpha`] before its scaling: the probability of guessing
+/// exactly `j` first challenges and then the second challenge with `aa` non-zero rounds, times
+/// `binom(t, aa) binom(t, w) (second_challenges - 1)^w`.
+#[inline]
+pub(crate) fn term_b_new<T: Float>(
+    scheme: &Scheme,
+    binomials: &Binomials,
+    guess: &FirstGuess<T>,
+    j: i64,
+    aa: i64,
+) -> T {
+    let exact = T::from_bigint(&sum_b_new_exact(scheme, binomials, j, aa));
+    guess.term(exact, scheme.t, j)
+}
+
+#[inline]
+fn sum_b_new<T: Float>(
+    scheme: &Scheme,
+    binomials: &Binomials,
+    ts: i64,
+    aa: i64,
+    guess: &FirstGuess<T>,
+) -> T {
+    let t = scheme.t;
+    let scale = binomials.get(t, aa) * scheme.second_scale();
+
+    (ts..=t)
+        .map(|j| term_b_new(scheme, binomials, guess, j, aa))
+        .sum::<T>()
+        / T::from_bigint(&scale)
+}
+
+/// Success probability of the second phase of the new attack, given the success of the first,
+/// together with the optimal number `alpha >= w` of non-zero second challenges to guess.
+#[inline]
+pub fn prob_b_new<T: Float>(scheme: &Scheme, ts: i64) -> (i64, T) {
+    let Scheme { t, w, .. } = *scheme;
+    let guess = FirstGuess::<T>::new(scheme);
+    let beta_prob = prob_beta::<T>(scheme, ts);
+    let binomials = Binomials::new(t);
+    let binom_tw = T::from_bigint(binomials.get(t, w));
+
+    let result = (w..=t)
+        .map(|aa| {
+            let prob = sum_b_new::<T>(scheme, &binomials, ts, aa, &guess);
+            (aa, prob)
+        })
+        .max_by(|(_, comp_a), (_, comp_b)| comp_a.partial_cmp(comp_b).unwrap())
+        .unwrap();
+
+    let prob = result.1 / (beta_prob * binom_tw);
+    if prob.is_nan() {
+        // TODO: warn of NaN
+        (result.0, T::zero())
+    } else {
+        (result.0, prob)
+    }
+}
+
+/// Success probability of the second phase of the new attack, given the success of the first,
+/// when guessing `aa` non-zero second challenges.
+#[inline]
+pub fn prob_b_new_alpha<T: Float>(scheme: &Scheme, ts: i64, aa: i64) -> T {
+    let Scheme { t, w, .. } = *scheme;
+    let guess = FirstGuess::<T>::new(scheme);
+    let beta_prob = prob_beta::<T>(scheme, ts);
+    let binomials = Binomials::new(t);
+    let binom_tw = T::from_bigint(binomials.get(t, w));
+
+    let prob = sum_b_new::<T>(scheme, &binomials, ts, aa, &guess) / (beta_prob * binom_tw);
+    if prob.is_nan() {
+        // TODO: warn of NaN
+        T::zero()
+    } else {
+        prob
+    }
+}
+
+/// Exact probabilities and costs of the attacks on a scheme, as pairs of a numerator and a
+/// denominator, against which the [`Float`] backends are checked.
+pub(crate) struct Exact<'a> {
+    scheme: &'a Scheme,
+    binomials: Binomials,
+}
+
+impl<'a> Exact<'a> {
+    pub(crate) fn new(scheme: &'a Scheme) -> Self {
+        Exact {
+            scheme,
+            binomials: Binomials::new(scheme.t),
+        }
+    }
+
+    /// Sum of `term(j) (first_challenges - 1)^(t - j)` for `j` in `ts..=t`, leaving out the
+    /// common factor `1/first_challenges^t` of the probabilities of guessing `j` first challenges.
+    fn sum(&self, ts: i64, term: impl Fn(i64) -> BigInt) -> BigInt {
+        let Scheme { t, .. } = *self.scheme;
+        let miss = BigInt::from(self.scheme.first_challenges - 1);
+        (ts..=t).map(|j| term(j) * miss.pow((t - j) as u32)).sum()
+    }
+
+    /// Exact [`prob_beta`].
+    pub(crate) fn prob_beta(&self, ts: i64) -> (BigInt, BigInt) {
+        let Scheme { t, .. } = *self.scheme;
+        let beta = self.sum(ts, |j| self.binomials.get(t, j).clone());
+        (
+            beta,
+            BigInt::from(self.scheme.first_challenges).pow(t as u32),
+        )
+    }
+
+    /// Exact [`prob_b`].
+    pub(crate) fn prob_b(&self, ts: i64) -> (BigInt, BigInt) {
+        let Scheme { t, w, .. } = *self.scheme;
+        let (beta, _) = self.prob_beta(ts);
+        let sum = self.sum(ts, |j| sum_b_exact(self.scheme, &self.binomials, j));
+        let binom_tw = self.binomials.get(t, w);
+        (sum, binom_tw.pow(2) * self.scheme.second_scale() * beta)
+    }
+
+    /// Exact [`prob_b_new_alpha`].
+    pub(crate) fn prob_b_new(&self, ts: i64, aa: i64) -> (BigInt, BigInt) {
+        let Scheme { t, w, .. } = *self.scheme;
+        let (beta, _) = self.prob_beta(ts);
+        let sum = self.sum(ts, |j| sum_b_new_exact(self.scheme, &self.binomials, j, aa));
+        let scale = self.binomials.get(t, aa) * self.binomials.get(t, w);
+        (sum, scale * self.scheme.second_scale() * beta)
+    }
+
+    /// Exact [`attack_cost`].
+    pub(crate) fn attack_cost(&self, ts: i64) -> (BigInt, BigInt) {
+        Self::cost(&self.prob_beta(ts), &self.prob_b(ts))
+    }
+
+    /// Exact [`attack_new_alpha_cost`].
+    pub(crate) fn attack_new_cost(&self, ts: i64, aa: i64) -> (BigInt, BigInt) {
+        Self::cost(&self.prob_beta(ts), &self.prob_b_new(ts, aa))
+    }
+
+    /// Cost `1/prob_beta + 1/prob_b` for the probability `prob_b` of the second phase.
+    fn cost(prob_beta: &(BigInt, BigInt), prob_b: &(BigInt, BigInt)) -> (BigInt, BigInt) {
+        let (beta_num, beta_den) = prob_beta;
+        let (b_num, b_den) = prob_b;
+        (beta_den * b_num + b_den * beta_num, beta_num * b_num)
+    }
+}
+
+/// Cost `1/prob_beta + 1/prob_b` of the original attack on `scheme` guessing `ts` first
+/// challenges, as estimated gate count.
+#[inline]
+pub fn attack_cost<T: Float>(scheme: &Scheme, ts: i64) -> T {
+    T::one() / prob_beta::<T>(scheme, ts) + T::one() / prob_b::<T>(scheme, ts)
+}
+
+/// Optimal `alpha` and cost `1/prob_beta + 1/prob_b_new` of the new attack on `scheme` guessing
+/// `ts` first challenges, as estimated gate count.
+#[inline]
+pub fn attack_new_cost<T: Float>(scheme: &Scheme, ts: i64) -> (i64, T) {
+    let beta_prob = prob_beta::<T>(scheme, ts);
+    let (aa, b_prob) = prob_b_new::<T>(scheme, ts);
+
+    (aa, T::one() / beta_prob + T::one() / b_prob)
+}
+
+/// Cost `1/prob_beta + 1/prob_b_new_alpha` of the new attack on `scheme` guessing `ts` first
+/// challenges and `aa` non-zero second challenges, as estimated gate count.
+#[inline]
+pub fn attack_new_alpha_cost<T: Float>(scheme: &Scheme, ts: i64, aa: i64) -> T {
+    T::one() / prob_beta::<T>(scheme, ts) + T::one() / prob_b_new_alpha::<T>(scheme, ts, aa)
+}
+
+/// Estimate cost of the original attack on `scheme`.
+///
+/// Returns the optimal `t*` and the cost as log2 of the estimated gate count. If `quiet` is
+/// [`true`] the progress bar is hidden.
+#[inline]
+pub fn estimate_attack<T: Float>(scheme: &Scheme, quiet: bool) -> (i64, f64) {
+    let result = (0..=u16::try_from(scheme.t).unwrap())
+        .into_par_iter()
+        .progress_with_style(get_default_pb_style(quiet))
+        .map(|ts| (attack_cost::<T>(scheme, ts as i64), ts))
+        .min_by(|(comp_a, _), (comp_b, _)| comp_a.partial_cmp(comp_b).unwrap())
+        .unwrap();
+
+    let complog = result.0.log2();
+    let ts = result.1;
+
+    (ts as i64, complog)
+}
+
+/// Estimate cost of the new attack on `scheme`.
+///
+/// Returns the optimal `t*` and `alpha` and the cost as log2 of the estimated gate count. If
+/// `quiet` is [`true`] the progress bar is hidden.
+#[inline]
+pub fn estimate_attack_new<T: Float>(scheme: &Scheme, quiet: bool) -> (i64, i64, f64) {
+    let result = (0..=u16::try_from(scheme.t).unwrap())
+        .into_par_iter()
+        .progress_with_style(get_default_pb_style(quiet))
+        .map(|ts| {
+            let (aa, comp) = attack_new_cost::<T>(scheme, ts as i64);
+            (comp, ts, aa)
+        })
+        .min_by(|(comp_a, _, _), (comp_b, _, _)| comp_a.partial_cmp(comp_b).unwrap())
+        .unwrap();
+
+    let complog = result.0.log2();
+    let ts = result.1;
+    let aa = result.2;
+
+    (ts as i64, aa, complog)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sum_b_exact, sum_b_new_exact, Scheme};
+    use crate::float::BigInt;
+    use crate::Binomials;
+
+    fn binom(n: i64, k: i64) -> i128 {
+        if k < 0 || k > n {
+            return 0;
+        }
+        (0..k).fold(1, |r, i| r * (n - i) as i128 / (i + 1) as i128)
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(Scheme::cross(127, 24, 16).check(), Ok(()));
+        assert_eq!(Scheme::cross(127, 0, 0).check(), Ok(()));
+        assert!(Scheme::cross(127, -1, 0).check().is_err());
+        assert!(Scheme::cross(127, 65536, 16).check().is_err());
+        assert!(Scheme::cross(127, 24, 25).check().is_err());
+        assert!(Scheme::cross(127, 24, -1).check().is_err());
+        assert!(Scheme::cross(2, 24, 16).check().is_err());
+        let scheme = Scheme {
+            second_challenges: 1,
+            ..Scheme::cross(127, 24, 16)
+        };
+        assert!(scheme.check().is_err());
+    }
+
+    #[test]
+    fn test_exact_sums() {
+        let scheme = Scheme {
+            second_challenges: 4,
+            ..Scheme::cross(127, 14, 9)
+        };
+        let Scheme { t, w, .. } = scheme;
+        let binomials = Binomials::new(t);
+        let pow = |k: i64| 3i128.pow(k as u32);
+
+        for j in 0..=t {
+            let expected: i128 = (0..=w)
+                .map(|ws| binom(j, ws).pow(2) * binom(t - j, w - ws) * pow(ws))
+                .sum();
+            assert_eq!(
+                sum_b_exact(&scheme, &binomials, j),
+                BigInt::from(expected * binom(t, j))
+            );
+
+            for aa in w..=t {
+                let expected: i128 = (0..=w)
+                    .map(|ws| binom(t - j, ws) * binom(j, aa - ws) * binom(j, w - ws) * pow(w - ws))
+                    .sum();
+                assert_eq!(
+                    sum_b_new_exact(&scheme, &binomials, j, aa),
+                    BigInt::from(expected * binom(t, j))
+                );
+            }
+        }
+    }
+
+    macro_rules! float_test {
+        ($name:ident: $type:ty) => {
+            mod $name {
+                use crate::float::Float;
+                use crate::scheme::{prob_b, prob_b_new, Scheme};
+
+                #[test]
+                fn test_second_challenges() {
+                    // more values for the second challenge make guessing it harder
+                    let binary = Scheme::cross(127, 64, 40);
+                    let ternary = Scheme {
+                        second_challenges: 3,
+                        ..binary
+                    };
+                    let ts = 12;
+
+                    let (_, prob_binary) = prob_b_new::<$type>(&binary, ts);
+                    let (_, prob_ternary) = prob_b_new::<$type>(&ternary, ts);
+                    assert!(prob_ternary < prob_binary);
+                    assert!(prob_b::<$type>(&ternary, ts) < prob_b::<$type>(&binary, ts));
+                    assert!(prob_ternary.log2() > -100.0);
+                }
+            }
+        };
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum,
+    }
+}
@@ -0,0 +1,235 @@
+//! Monte Carlo simulation of the forgery, used to validate the analytic success probabilities.
+//!
+//! The simulator samples challenges and adversary guesses exactly as in the two strategies:
+//!
+//! 1. In the first phase the adversary guesses the first challenge of each of the `t` rounds,
+//!    which is drawn uniformly from a space of size `p-1`. The phase succeeds if at least `t*`
+//!    guesses are correct; the set of correctly guessed rounds is denoted by `S`.
+//! 2. In the second phase the adversary guesses a binary vector of weight `alpha` (`alpha = w` in
+//!    Proposition 18, any `alpha >= w` in [`BLPST25`](https://eprint.iacr.org/2025/127)), while
+//!    the second challenge is drawn uniformly among the vectors of weight `w`. The phase succeeds
+//!    if the two vectors agree on every round outside `S`.
+//!
+//! The estimates only make sense for toy parameters (e.g. `t <= 40` and small `p`), where the
+//! success probabilities are large enough to be observed.
+
+use rand::seq::index;
+use rand::Rng;
+
+/// Two-sided confidence interval around an empirical probability.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Interval {
+    /// Observed frequency.
+    pub estimate: f64,
+    /// Lower end of the interval.
+    pub lower: f64,
+    /// Upper end of the interval.
+    pub upper: f64,
+}
+
+impl Interval {
+    /// Wilson score interval for `successes` out of `trials` Bernoulli samples.
+    ///
+    /// `z` is the quantile of the standard normal distribution for the desired confidence level
+    /// (e.g. `1.96` for 95%).
+    pub fn wilson(successes: u64, trials: u64, z: f64) -> Self {
+        if trials == 0 {
+            return Interval {
+                estimate: f64::NAN,
+                lower: 0.0,
+                upper: 1.0,
+            };
+        }
+
+        let n = trials as f64;
+        let p = successes as f64 / n;
+        let z2 = z * z;
+        let denom = 1.0 + z2 / n;
+        let center = (p + z2 / (2.0 * n)) / denom;
+        let half = z / denom * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+        Interval {
+            estimate: p,
+            lower: (center - half).max(0.0),
+            upper: (center + half).min(1.0),
+        }
+    }
+
+    /// Returns [`true`] if `value` lies within the interval.
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+}
+
+/// Outcome of a simulation of the forgery.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Simulation {
+    /// Number of first phase attempts.
+    pub trials: u64,
+    /// Number of successful first phases.
+    pub first_successes: u64,
+    /// Number of successful second phases, among the successful first phases.
+    pub second_successes: u64,
+    /// Success probability of the first phase, comparable with [`prob_beta`](crate::prob_beta).
+    pub beta: Interval,
+    /// Success probability of the second phase conditioned on the success of the first one,
+    /// comparable with [`prob_b`](crate::prob_b) or [`prob_b_new_alpha`](crate::prob_b_new_alpha).
+    pub b: Interval,
+}
+
+fn sample_weight<R: Rng + ?Sized>(rng: &mut R, t: i64, w: i64) -> Vec<bool> {
+    let mut v = vec![false; t as usize];
+    for i in index::sample(rng, t as usize, w as usize) {
+        v[i] = true;
+    }
+    v
+}
+
+/// Simulate the original attack as described in Proposition 18 of the CROSS specification.
+///
+/// # Arguments
+///
+/// * `t` - Number of parallel iterations.
+/// * `ts` - Number of rounds `t*` that must be guessed in the first phase.
+/// * `w` - Fixed-weight parameter.
+/// * `p` - Characteristic of the finite field. The challenge space for the first challenge has size `p-1`.
+/// * `trials` - Number of first phase attempts.
+/// * `z` - Normal quantile of the confidence intervals.
+/// * `rng` - Source of randomness.
+pub fn simulate_attack<R: Rng + ?Sized>(
+    t: i64,
+    ts: i64,
+    w: i64,
+    p: i64,
+    trials: u64,
+    z: f64,
+    rng: &mut R,
+) -> Simulation {
+    simulate_attack_new(t, ts, w, p, w, trials, z, rng)
+}
+
+/// Simulate the new attack as described in [`BLPST25`](https://eprint.iacr.org/2025/127), where
+/// the adversary guesses a second challenge of weight `aa`.
+///
+/// # Arguments
+///
+/// * `t` - Number of parallel iterations.
+/// * `ts` - Number of rounds `t*` that must be guessed in the first phase.
+/// * `w` - Fixed-weight parameter.
+/// * `p` - Characteristic of the finite field. The challenge space for the first challenge has size `p-1`.
+/// * `aa` - Weight `alpha` of the guess for the second challenge.
+/// * `trials` - Number of first phase attempts.
+/// * `z` - Normal quantile of the confidence intervals.
+/// * `rng` - Source of randomness.
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_attack_new<R: Rng + ?Sized>(
+    t: i64,
+    ts: i64,
+    w: i64,
+    p: i64,
+    aa: i64,
+    trials: u64,
+    z: f64,
+    rng: &mut R,
+) -> Simulation {
+    assert!(
+        0 <= w && w <= aa && aa <= t,
+        "expected 0 <= w <= alpha <= t"
+    );
+    assert!(p > 2, "expected p > 2");
+
+    let mut first_successes = 0;
+    let mut second_successes = 0;
+
+    for _ in 0..trials {
+        // first phase: guess the first challenge of every round (w.l.o.g. always guess 1)
+        let guessed: Vec<bool> = (0..t).map(|_| rng.gen_range(1..p) == 1).collect();
+        if (guessed.iter().filter(|&&g| g).count() as i64) < ts {
+            continue;
+        }
+        first_successes += 1;
+
+        // second phase: the guess must match the challenge outside S
+        let b = sample_weight(rng, t, w);
+        let guess = sample_weight(rng, t, aa);
+        if (0..t as usize).all(|i| guessed[i] || b[i] == guess[i]) {
+            second_successes += 1;
+        }
+    }
+
+    Simulation {
+        trials,
+        first_successes,
+        second_successes,
+        beta: Interval::wilson(first_successes, trials, z),
+        b: Interval::wilson(second_successes, first_successes, z),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    macro_rules! float_test {
+        ($name:ident: $type:ty) => {
+            mod $name {
+                use crate::simulate::{simulate_attack, simulate_attack_new};
+                use crate::{float::Float, prob_b, prob_b_new, prob_b_new_alpha, prob_beta};
+                use rand::rngs::StdRng;
+                use rand::SeedableRng;
+
+                const TRIALS: u64 = 40_000;
+                const Z: f64 = 4.0;
+
+                fn to_f64(x: $type) -> f64 {
+                    x.log2().exp2()
+                }
+
+                #[test]
+                fn test_simulate_attack() {
+                    let mut rng = StdRng::seed_from_u64(18);
+                    for (p, t, w, ts) in [(3, 12, 6, 6), (5, 20, 16, 5), (7, 16, 10, 3)] {
+                        let sim = simulate_attack(t, ts, w, p, TRIALS, Z, &mut rng);
+                        let beta = to_f64(prob_beta::<$type>(t, ts, p));
+                        let b = to_f64(prob_b::<$type>(t, ts, w, p));
+
+                        assert!(sim.beta.contains(beta), "{beta} not in {:?}", sim.beta);
+                        assert!(sim.b.contains(b), "{b} not in {:?}", sim.b);
+                    }
+                }
+
+                #[test]
+                fn test_simulate_attack_new() {
+                    let mut rng = StdRng::seed_from_u64(25);
+                    for (p, t, w, ts) in [(3, 12, 6, 6), (5, 20, 16, 5)] {
+                        let (aa_best, _) = prob_b_new::<$type>(t, ts, w, p);
+                        for aa in [w, aa_best, t] {
+                            let sim = simulate_attack_new(t, ts, w, p, aa, TRIALS, Z, &mut rng);
+                            let b = to_f64(prob_b_new_alpha::<$type>(t, ts, w, p, aa));
+
+                            assert!(sim.b.contains(b), "alpha = {aa}: {b} not in {:?}", sim.b);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum,
+    }
+}
@@ -0,0 +1,137 @@
+//! Parameter sweeps over the quantities optimised by [`estimate_attack`](crate::estimate_attack)
+//! and [`estimate_attack_new`](crate::estimate_attack_new).
+//!
+//! Instead of returning only the optimum, these functions return the whole cost curve, which is
+//! useful for plotting and for inspecting how sharp the optimum is.
+
+use crate::float::Float;
+use crate::get_default_pb_style;
+use crate::scheme::{attack_cost, attack_new_alpha_cost, attack_new_cost, Scheme};
+use indicatif::ParallelProgressIterator;
+use rayon::prelude::*;
+
+/// Cost of the original attack for every value of `t*` in `0..=t`.
+///
+/// Returns a vector of `(t*, cost)` pairs sorted by `t*`, where the cost is given as log2 of the
+/// estimated gate count.
+///
+/// # Arguments
+///
+/// * `t` - Number of parallel iterations.
+/// * `w` - Fixed-weight parameter.
+/// * `p` - Characteristic of the finite field. The challenge space for the first challenge has size `p-1`.
+/// * `quiet` - If [`true`] hide the progress bar.
+pub fn attack_costs<T: Float>(t: i64, w: i64, p: i64, quiet: bool) -> Vec<(i64, f64)> {
+    let scheme = Scheme::cross(p, t, w);
+    (0..=u16::try_from(t).unwrap())
+        .into_par_iter()
+        .progress_with_style(get_default_pb_style(quiet))
+        .map(|ts| (ts as i64, attack_cost::<T>(&scheme, ts as i64).log2()))
+        .collect()
+}
+
+/// Cost of the new attack for every value of `t*` in `0..=t`, using the optimal `alpha` for each.
+///
+/// Returns a vector of `(t*, alpha, cost)` triples sorted by `t*`, where the cost is given as log2
+/// of the estimated gate count.
+///
+/// # Arguments
+///
+/// * `t` - Number of parallel iterations.
+/// * `w` - Fixed-weight parameter.
+/// * `p` - Characteristic of the finite field. The challenge space for the first challenge has size `p-1`.
+/// * `quiet` - If [`true`] hide the progress bar.
+pub fn attack_new_costs<T: Float>(t: i64, w: i64, p: i64, quiet: bool) -> Vec<(i64, i64, f64)> {
+    let scheme = Scheme::cross(p, t, w);
+    (0..=u16::try_from(t).unwrap())
+        .into_par_iter()
+        .progress_with_style(get_default_pb_style(quiet))
+        .map(|ts| {
+            let (aa, comp) = attack_new_cost::<T>(&scheme, ts as i64);
+            (ts as i64, aa, comp.log2())
+        })
+        .collect()
+}
+
+/// Cost of the new attack for a fixed `t*` and every value of `alpha` in `w..=t`.
+///
+/// Returns a vector of `(alpha, cost)` pairs sorted by `alpha`, where the cost is given as log2 of
+/// the estimated gate count.
+///
+/// # Arguments
+///
+/// * `t` - Number of parallel iterations.
+/// * `ts` - Number of rounds `t*` guessed in the first phase.
+/// * `w` - Fixed-weight parameter.
+/// * `p` - Characteristic of the finite field. The challenge space for the first challenge has size `p-1`.
+/// * `quiet` - If [`true`] hide the progress bar.
+pub fn attack_new_alpha_costs<T: Float>(
+    t: i64,
+    ts: i64,
+    w: i64,
+    p: i64,
+    quiet: bool,
+) -> Vec<(i64, f64)> {
+    let scheme = Scheme::cross(p, t, w);
+    (u16::try_from(w).unwrap()..=u16::try_from(t).unwrap())
+        .into_par_iter()
+        .progress_with_style(get_default_pb_style(quiet))
+        .map(|aa| {
+            let comp = attack_new_alpha_cost::<T>(&scheme, ts, aa as i64);
+            (aa as i64, comp.log2())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    macro_rules! float_test {
+        ($name:ident: $type:ty) => {
+            mod $name {
+                use crate::sweep::{attack_costs, attack_new_alpha_costs, attack_new_costs};
+                use crate::{estimate_attack, estimate_attack_new};
+
+                #[test]
+                fn test_minimum() {
+                    for (p, t, w) in [(127, 24, 16), (13, 24, 18), (7, 40, 30)] {
+                        // the first minimum in order of t*, as in the estimates
+                        let minimum = |costs: &[(i64, f64)]| {
+                            *costs
+                                .iter()
+                                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                                .unwrap()
+                        };
+
+                        let costs = attack_costs::<$type>(t, w, p, true);
+                        assert_eq!(minimum(&costs), estimate_attack::<$type>(t, w, p, true));
+
+                        let (ts, aa, cost) = estimate_attack_new::<$type>(t, w, p, true);
+                        let costs = attack_new_costs::<$type>(t, w, p, true);
+                        let costs: Vec<_> = costs.iter().map(|&(ts, _, cost)| (ts, cost)).collect();
+                        assert_eq!(minimum(&costs), (ts, cost));
+                        let costs = attack_new_alpha_costs::<$type>(t, ts, w, p, true);
+                        assert_eq!(minimum(&costs), (aa, cost));
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum,
+    }
+}
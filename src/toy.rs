@@ -0,0 +1,638 @@
+//! Toy end-to-end forgery on a miniature CROSS instance.
+//!
+//! This module implements a deliberately small and insecure version of the CROSS signature over
+//! the restricted syndrome decoding problem (R-SDP), together with an adversary that follows the
+//! strategy of [`BLPST25`](https://eprint.iacr.org/2025/127). Running the adversary on tiny
+//! parameters produces a signature that verifies without knowledge of the secret key, and counts
+//! the attempts it needed so that they can be compared with the cost predicted by
+//! [`estimate_attack_new`](crate::estimate_attack_new).
+//!
+//! The structure of the scheme follows CROSS:
+//!
+//! * the public key is a parity-check matrix `H = [V | I]` over `Fp` and a syndrome `s = e H^T`,
+//!   where the secret `e` has entries in the cyclic subgroup `E` of `Fp^*` generated by `g`;
+//! * each of the `t` rounds commits to `cmt_0 = Hash(s', v)` and `cmt_1 = Hash(u', e')`;
+//! * the first challenge assigns a `beta` in `Fp^*` to each round, and the prover answers with
+//!   `y = u' + beta e'`;
+//! * the second challenge is a binary vector of weight `w`: rounds with `b = 0` open `(y, v)`,
+//!   rounds with `b = 1` open `(u', e')`.
+//!
+//! Hashes are computed with [`DefaultHasher`] and challenges are expanded with [`StdRng`]: neither
+//! is meant to be cryptographically secure.
+
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::{Rng, SeedableRng};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+fn hash<H: Hash>(value: &H) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn is_prime(p: i64) -> bool {
+    p >= 2 && (2..).take_while(|d| d * d <= p).all(|d| p % d != 0)
+}
+
+fn pow_mod(base: i64, mut exp: i64, p: i64) -> i64 {
+    let mut base = base.rem_euclid(p);
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % p;
+        }
+        base = base * base % p;
+        exp >>= 1;
+    }
+    result
+}
+
+/// Public parameters of a toy R-SDP instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Prime order of the finite field.
+    pub p: i64,
+    /// Generator of the restricted subgroup `E` of `Fp^*`.
+    pub g: i64,
+    /// Code length.
+    pub n: usize,
+    /// Code dimension.
+    pub k: usize,
+    /// Number of parallel repetitions.
+    pub t: i64,
+    /// Fixed-weight parameter for the second challenge.
+    pub w: i64,
+}
+
+impl Params {
+    /// Checks that `p` is prime and that `g` is in `Fp^*`, so that `g` generates a subgroup of
+    /// `Fp^*`, and that `k <= n` and `w <= t`.
+    pub fn check(&self) -> Result<(), String> {
+        if !is_prime(self.p) {
+            Err(format!("p = {} is not prime", self.p))
+        } else if self.g.rem_euclid(self.p) == 0 {
+            Err(format!("g = {} is not in Fp^* for p = {}", self.g, self.p))
+        } else if self.k > self.n {
+            Err("k must be at most n".to_string())
+        } else if !(0..=self.t).contains(&self.w) {
+            Err("w must be between 0 and t".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Elements of the restricted subgroup `E`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters do not pass [`Params::check`].
+    pub fn restricted_group(&self) -> Vec<i64> {
+        if let Err(err) = self.check() {
+            panic!("invalid toy parameters: {err}");
+        }
+
+        let mut group = vec![1];
+        let mut x = self.g.rem_euclid(self.p);
+        while x != 1 {
+            group.push(x);
+            x = (x * self.g).rem_euclid(self.p);
+        }
+        group
+    }
+
+    fn sample_restricted<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<i64> {
+        let group = self.restricted_group();
+        (0..self.n)
+            .map(|_| group[rng.gen_range(0..group.len())])
+            .collect()
+    }
+
+    fn sample_vector<R: Rng + ?Sized>(&self, rng: &mut R, len: usize) -> Vec<i64> {
+        (0..len).map(|_| rng.gen_range(0..self.p)).collect()
+    }
+
+    fn is_restricted(&self, v: &[i64]) -> bool {
+        let group = self.restricted_group();
+        v.len() == self.n && v.iter().all(|x| group.contains(x))
+    }
+
+    fn mul(&self, a: &[i64], b: &[i64]) -> Vec<i64> {
+        a.iter().zip(b).map(|(x, y)| x * y % self.p).collect()
+    }
+
+    fn inv(&self, a: &[i64]) -> Vec<i64> {
+        a.iter().map(|&x| pow_mod(x, self.p - 2, self.p)).collect()
+    }
+
+    /// Returns `a + c b`.
+    fn add_scaled(&self, a: &[i64], c: i64, b: &[i64]) -> Vec<i64> {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x + c * y).rem_euclid(self.p))
+            .collect()
+    }
+
+    /// First challenge: one element of `Fp^*` per round.
+    fn first_challenge(&self, digest: u64) -> Vec<i64> {
+        let mut rng = StdRng::seed_from_u64(digest);
+        (0..self.t).map(|_| rng.gen_range(1..self.p)).collect()
+    }
+
+    /// Second challenge: a binary vector of weight `w`.
+    fn second_challenge(&self, digest: u64) -> Vec<bool> {
+        let mut rng = StdRng::seed_from_u64(digest);
+        sample_weight(&mut rng, self.t, self.w)
+    }
+}
+
+fn sample_weight<R: Rng + ?Sized>(rng: &mut R, t: i64, w: i64) -> Vec<bool> {
+    let mut v = vec![false; t as usize];
+    for i in index::sample(rng, t as usize, w as usize) {
+        v[i] = true;
+    }
+    v
+}
+
+/// Public key of a toy R-SDP instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    /// Public parameters.
+    pub params: Params,
+    /// Non-systematic part `V` of the parity-check matrix, with `n-k` rows of length `k`.
+    pub v: Vec<Vec<i64>>,
+    /// Syndrome `s = e H^T`.
+    pub s: Vec<i64>,
+}
+
+impl PublicKey {
+    /// Returns `x H^T`.
+    fn syndrome(&self, x: &[i64]) -> Vec<i64> {
+        let Params { p, k, .. } = self.params;
+        self.v
+            .iter()
+            .zip(&x[k..])
+            .map(|(row, x_sys)| {
+                row.iter()
+                    .zip(&x[..k])
+                    .fold(*x_sys, |acc, (h, x)| (acc + h * x) % p)
+            })
+            .collect()
+    }
+
+    fn cmt_0(&self, y: &[i64], v: &[i64], beta: i64) -> u64 {
+        let params = &self.params;
+        let s_prime = params.add_scaled(&self.syndrome(&params.mul(v, y)), -beta, &self.s);
+        hash(&(s_prime, v))
+    }
+
+    /// Verify `signature` on message `msg`.
+    pub fn verify(&self, msg: u64, signature: &Signature) -> bool {
+        let params = &self.params;
+        if signature.responses.len() != params.t as usize {
+            return false;
+        }
+
+        let digest_chall_1 = hash(&(signature.digest_cmt, msg, signature.salt));
+        let beta = params.first_challenge(digest_chall_1);
+        let b = params.second_challenge(signature.digest_chall_2);
+
+        let mut cmt = Vec::with_capacity(signature.responses.len());
+        let mut ys = Vec::with_capacity(signature.responses.len());
+        for ((response, &beta), &b) in signature.responses.iter().zip(&beta).zip(&b) {
+            match (response, b) {
+                (Response::Zero { y, v, cmt_1 }, false) => {
+                    if !params.is_restricted(v) || y.len() != params.n {
+                        return false;
+                    }
+                    cmt.push((self.cmt_0(y, v, beta), *cmt_1));
+                    ys.push(y.clone());
+                }
+                (
+                    Response::One {
+                        u_prime,
+                        e_prime,
+                        cmt_0,
+                    },
+                    true,
+                ) => {
+                    if !params.is_restricted(e_prime) || u_prime.len() != params.n {
+                        return false;
+                    }
+                    cmt.push((*cmt_0, hash(&(u_prime, e_prime))));
+                    ys.push(params.add_scaled(u_prime, beta, e_prime));
+                }
+                _ => return false,
+            }
+        }
+
+        hash(&cmt) == signature.digest_cmt
+            && hash(&(ys, digest_chall_1)) == signature.digest_chall_2
+    }
+}
+
+/// Secret key of a toy R-SDP instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretKey {
+    /// Restricted error vector `e`.
+    pub e: Vec<i64>,
+}
+
+/// Generate a random key pair.
+///
+/// # Panics
+///
+/// Panics if `params` do not pass [`Params::check`].
+pub fn keygen<R: Rng + ?Sized>(params: Params, rng: &mut R) -> (PublicKey, SecretKey) {
+    if let Err(err) = params.check() {
+        panic!("invalid toy parameters: {err}");
+    }
+    let v = (0..params.n - params.k)
+        .map(|_| params.sample_vector(rng, params.k))
+        .collect();
+    let e = params.sample_restricted(rng);
+    let mut pk = PublicKey {
+        params,
+        v,
+        s: Vec::new(),
+    };
+    pk.s = pk.syndrome(&e);
+
+    (pk, SecretKey { e })
+}
+
+/// Response of a single round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// Response to `b = 0`.
+    Zero {
+        /// Masked vector `y`.
+        y: Vec<i64>,
+        /// Restricted vector `v`.
+        v: Vec<i64>,
+        /// Commitment that cannot be recomputed.
+        cmt_1: u64,
+    },
+    /// Response to `b = 1`.
+    One {
+        /// Random vector `u'`.
+        u_prime: Vec<i64>,
+        /// Restricted vector `e'`.
+        e_prime: Vec<i64>,
+        /// Commitment that cannot be recomputed.
+        cmt_0: u64,
+    },
+}
+
+/// Toy CROSS signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    /// Salt used to derive the first challenge.
+    pub salt: u64,
+    /// Digest of all commitments.
+    pub digest_cmt: u64,
+    /// Digest from which the second challenge is derived.
+    pub digest_chall_2: u64,
+    /// Responses, one for each round.
+    pub responses: Vec<Response>,
+}
+
+/// Opening of a round, from which both responses can be built.
+struct Round {
+    u_prime: Vec<i64>,
+    e_prime: Vec<i64>,
+    v: Vec<i64>,
+    s_prime: Vec<i64>,
+    cmt_0: u64,
+    cmt_1: u64,
+}
+
+impl Round {
+    fn respond(&self, y: Vec<i64>, b: bool) -> Response {
+        match b {
+            false => Response::Zero {
+                y,
+                v: self.v.clone(),
+                cmt_1: self.cmt_1,
+            },
+            true => Response::One {
+                u_prime: self.u_prime.clone(),
+                e_prime: self.e_prime.clone(),
+                cmt_0: self.cmt_0,
+            },
+        }
+    }
+}
+
+/// Sign message `msg` honestly.
+pub fn sign<R: Rng + ?Sized>(pk: &PublicKey, sk: &SecretKey, msg: u64, rng: &mut R) -> Signature {
+    let params = &pk.params;
+
+    let rounds: Vec<Round> = (0..params.t)
+        .map(|_| {
+            let e_prime = params.sample_restricted(rng);
+            let u_prime = params.sample_vector(rng, params.n);
+            let v = params.mul(&sk.e, &params.inv(&e_prime));
+            let s_prime = pk.syndrome(&params.mul(&v, &u_prime));
+            let cmt_0 = hash(&(&s_prime, &v));
+            let cmt_1 = hash(&(&u_prime, &e_prime));
+            Round {
+                u_prime,
+                e_prime,
+                v,
+                s_prime,
+                cmt_0,
+                cmt_1,
+            }
+        })
+        .collect();
+
+    let digest_cmt = hash(
+        &rounds
+            .iter()
+            .map(|r| (r.cmt_0, r.cmt_1))
+            .collect::<Vec<_>>(),
+    );
+    let salt = rng.gen();
+    let digest_chall_1 = hash(&(digest_cmt, msg, salt));
+    let beta = params.first_challenge(digest_chall_1);
+
+    let ys: Vec<Vec<i64>> = rounds
+        .iter()
+        .zip(&beta)
+        .map(|(r, &beta)| params.add_scaled(&r.u_prime, beta, &r.e_prime))
+        .collect();
+    let digest_chall_2 = hash(&(&ys, digest_chall_1));
+    let b = params.second_challenge(digest_chall_2);
+
+    Signature {
+        salt,
+        digest_cmt,
+        digest_chall_2,
+        responses: rounds
+            .iter()
+            .zip(ys)
+            .zip(b)
+            .map(|((r, y), b)| r.respond(y, b))
+            .collect(),
+    }
+}
+
+/// Outcome of a forgery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forgery {
+    /// Forged signature.
+    pub signature: Signature,
+    /// Number of first challenges computed.
+    pub first_attempts: u64,
+    /// Number of second challenges computed.
+    pub second_attempts: u64,
+}
+
+impl Forgery {
+    /// Total number of challenges computed, comparable with `2^cost` from
+    /// [`estimate_attack_new`](crate::estimate_attack_new).
+    pub fn attempts(&self) -> u64 {
+        self.first_attempts + self.second_attempts
+    }
+}
+
+/// Forge a signature on message `msg` following the strategy of [`BLPST25`](https://eprint.iacr.org/2025/127).
+///
+/// The adversary fixes a guess `b'` of weight `aa` for the second challenge, and prepares every
+/// round for a guessed first challenge. It then grinds the salt until at least `ts` first
+/// challenges are guessed correctly (the set `S`), and grinds the second challenge until it agrees
+/// with `b'` outside `S`. Rounds in `S` can answer both values of `b`, while the other rounds can
+/// only answer the value prescribed by `b'`: for `b' = 1` the response is honest, for `b' = 0` the
+/// masked vector `y` is obtained by solving the syndrome equation, which provides the randomness
+/// needed to grind the second challenge. If no such round exists the adversary starts over.
+///
+/// # Arguments
+///
+/// * `pk` - Public key under attack.
+/// * `msg` - Message to be signed.
+/// * `ts` - Number of rounds `t*` to be guessed in the first phase.
+/// * `aa` - Weight `alpha` of the guess for the second challenge.
+/// * `rng` - Source of randomness.
+pub fn forge<R: Rng + ?Sized>(pk: &PublicKey, msg: u64, ts: i64, aa: i64, rng: &mut R) -> Forgery {
+    let params = &pk.params;
+    assert!(params.w <= aa && aa <= params.t, "expected w <= alpha <= t");
+
+    let mut first_attempts = 0;
+    let mut second_attempts = 0;
+
+    loop {
+        let b_guess = sample_weight(rng, params.t, aa);
+        let beta_guess: Vec<i64> = (0..params.t).map(|_| rng.gen_range(1..params.p)).collect();
+
+        // commit to rounds that are consistent for the guessed first challenge
+        let rounds: Vec<Round> = beta_guess
+            .iter()
+            .map(|&beta| {
+                let e_prime = params.sample_restricted(rng);
+                let u_prime = params.sample_vector(rng, params.n);
+                let v = params.sample_restricted(rng);
+                let y = params.add_scaled(&u_prime, beta, &e_prime);
+                let s_prime = params.add_scaled(&pk.syndrome(&params.mul(&v, &y)), -beta, &pk.s);
+                let cmt_0 = hash(&(&s_prime, &v));
+                let cmt_1 = hash(&(&u_prime, &e_prime));
+                Round {
+                    u_prime,
+                    e_prime,
+                    v,
+                    s_prime,
+                    cmt_0,
+                    cmt_1,
+                }
+            })
+            .collect();
+        let digest_cmt = hash(
+            &rounds
+                .iter()
+                .map(|r| (r.cmt_0, r.cmt_1))
+                .collect::<Vec<_>>(),
+        );
+
+        // first phase: grind the salt until at least t* first challenges are guessed
+        let (salt, digest_chall_1, beta, guessed) = loop {
+            first_attempts += 1;
+            let salt: u64 = rng.gen();
+            let digest_chall_1 = hash(&(digest_cmt, msg, salt));
+            let beta = params.first_challenge(digest_chall_1);
+            let guessed: Vec<bool> = beta.iter().zip(&beta_guess).map(|(a, b)| a == b).collect();
+            if guessed.iter().filter(|&&g| g).count() as i64 >= ts {
+                break (salt, digest_chall_1, beta, guessed);
+            }
+        };
+
+        // second phase: grind y in the rounds answered with b = 0 outside S
+        let free: Vec<bool> = guessed
+            .iter()
+            .zip(&b_guess)
+            .map(|(&g, &b)| !g && !b)
+            .collect();
+        loop {
+            second_attempts += 1;
+            let ys: Vec<Vec<i64>> = rounds
+                .iter()
+                .zip(&beta)
+                .zip(&free)
+                .map(|((r, &beta), &free)| match free {
+                    false => params.add_scaled(&r.u_prime, beta, &r.e_prime),
+                    true => {
+                        // find z with z H^T = s' + beta s, then y = v^-1 z
+                        let mut z = params.sample_vector(rng, params.k);
+                        let target = params.add_scaled(&r.s_prime, beta, &pk.s);
+                        let offset =
+                            pk.syndrome(&[z.clone(), vec![0; params.n - params.k]].concat());
+                        z.extend(params.add_scaled(&target, -1, &offset));
+                        params.mul(&params.inv(&r.v), &z)
+                    }
+                })
+                .collect();
+            let digest_chall_2 = hash(&(&ys, digest_chall_1));
+            let b = params.second_challenge(digest_chall_2);
+
+            let success = (0..params.t as usize).all(|i| guessed[i] || b[i] == b_guess[i]);
+            if success {
+                let signature = Signature {
+                    salt,
+                    digest_cmt,
+                    digest_chall_2,
+                    responses: rounds
+                        .iter()
+                        .zip(ys)
+                        .zip(b)
+                        .map(|((r, y), b)| r.respond(y, b))
+                        .collect(),
+                };
+                return Forgery {
+                    signature,
+                    first_attempts,
+                    second_attempts,
+                };
+            }
+
+            if !free.contains(&true) {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keygen, sign, Params, Response};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const PARAMS: Params = Params {
+        p: 13,
+        g: 3,
+        n: 8,
+        k: 4,
+        t: 24,
+        w: 18,
+    };
+
+    #[test]
+    fn test_restricted_group() {
+        assert_eq!(PARAMS.restricted_group(), vec![1, 3, 9]);
+        let params = Params { g: -1, ..PARAMS };
+        assert_eq!(params.restricted_group(), vec![1, 12]);
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(PARAMS.check(), Ok(()));
+        assert!(Params {
+            p: 2,
+            g: 2,
+            ..PARAMS
+        }
+        .check()
+        .is_err());
+        assert!(Params { p: 15, ..PARAMS }.check().is_err());
+        assert!(Params { g: 26, ..PARAMS }.check().is_err());
+        assert!(Params { k: 9, ..PARAMS }.check().is_err());
+        assert!(Params { w: 25, ..PARAMS }.check().is_err());
+        assert_eq!(
+            Params {
+                p: 2,
+                g: 1,
+                ..PARAMS
+            }
+            .restricted_group(),
+            vec![1]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is not in Fp^*")]
+    fn test_restricted_group_invalid() {
+        Params {
+            p: 2,
+            g: 2,
+            ..PARAMS
+        }
+        .restricted_group();
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (pk, sk) = keygen(PARAMS, &mut rng);
+        let signature = sign(&pk, &sk, 42, &mut rng);
+
+        assert!(pk.verify(42, &signature));
+        assert!(!pk.verify(43, &signature));
+
+        let mut tampered = signature.clone();
+        match &mut tampered.responses[0] {
+            Response::Zero { y, .. } => y[0] = (y[0] + 1) % PARAMS.p,
+            Response::One { u_prime, .. } => u_prime[0] = (u_prime[0] + 1) % PARAMS.p,
+        }
+        assert!(!pk.verify(42, &tampered));
+    }
+
+    macro_rules! float_test {
+        ($name:ident: $type:ty) => {
+            mod $name {
+                use super::PARAMS;
+                use crate::toy::{forge, keygen};
+                use rand::rngs::StdRng;
+                use rand::SeedableRng;
+
+                #[test]
+                fn test_forge() {
+                    let mut rng = StdRng::seed_from_u64(1);
+                    let (pk, _) = keygen(PARAMS, &mut rng);
+                    let (ts, aa, _) =
+                        crate::estimate_attack_new::<$type>(PARAMS.t, PARAMS.w, PARAMS.p, true);
+                    let forgery = forge(&pk, 42, ts, aa, &mut rng);
+
+                    assert!(pk.verify(42, &forgery.signature));
+                    assert!(forgery.attempts() >= 2);
+                }
+            }
+        };
+    }
+
+    macro_rules! tests {
+        ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+            $(
+                #[cfg($meta)]
+                float_test! { $name: $type }
+            )*
+        };
+    }
+
+    tests! {
+        #[cfg(feature = "inexact")] f64: crate::float::F64Num,
+        #[cfg(feature = "inexact")] ext_f64: crate::float::ExtF64Num,
+        #[cfg(feature = "rug")] rug: crate::float::RugNum,
+        #[cfg(feature = "dashu")] dashu: crate::float::DashuNum,
+        #[cfg(feature = "nightly-float")] f128: crate::float::F128Num,
+        #[cfg(feature = "double-double")] dd: crate::float::DdNum,
+    }
+}
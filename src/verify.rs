@@ -0,0 +1,428 @@
+//! Consistency checks between the [`Float`] backends.
+//!
+//! The backends differ in precision (e.g. [`DashuNum`](crate::float::DashuNum) only keeps 32
+//! significant bits and never reports NaN), so the same estimate may silently drift from one
+//! backend to another. [`check_consistency`] evaluates the probabilities and the estimators with
+//! every enabled backend, compares their values with the exact ones and their optima with the ones
+//! of the most precise backend.
+//!
+//! The relative errors are computed by each backend from its own values and the exact fractions,
+//! rather than from the log2 returned by the estimators, whose [`f64`] cannot resolve them below
+//! about `1e-14`.
+
+use crate::float::{Backend, BigInt, Float};
+use crate::scheme::{self, Exact, Scheme};
+use crate::{estimate_attack, estimate_attack_new, prob_b, prob_b_new, prob_beta};
+use std::fmt;
+
+/// Quantities compared by [`check_consistency`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Quantity {
+    /// [`prob_beta`] at the chosen `t*`.
+    ProbBeta,
+    /// [`prob_b`] at the chosen `t*`.
+    ProbB,
+    /// [`prob_b_new`] at the chosen `t*`.
+    ProbBNew,
+    /// Cost of [`estimate_attack`].
+    EstimateAttack,
+    /// Cost of [`estimate_attack_new`].
+    EstimateAttackNew,
+}
+
+impl Quantity {
+    /// All the compared quantities.
+    pub const ALL: [Quantity; 5] = [
+        Quantity::ProbBeta,
+        Quantity::ProbB,
+        Quantity::ProbBNew,
+        Quantity::EstimateAttack,
+        Quantity::EstimateAttackNew,
+    ];
+
+    /// Name of the library function computing the quantity.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quantity::ProbBeta => "prob_beta",
+            Quantity::ProbB => "prob_b",
+            Quantity::ProbBNew => "prob_b_new",
+            Quantity::EstimateAttack => "estimate_attack",
+            Quantity::EstimateAttackNew => "estimate_attack_new",
+        }
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Results of a single backend, as log2 of the probabilities and costs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Evaluation {
+    /// Backend used for the evaluation.
+    pub backend: Backend,
+    /// log2 of [`prob_beta`].
+    pub prob_beta: f64,
+    /// log2 of [`prob_b`].
+    pub prob_b: f64,
+    /// Optimal `alpha` and log2 of [`prob_b_new`].
+    pub prob_b_new: (i64, f64),
+    /// Optimal `t*` and cost of [`estimate_attack`].
+    pub attack: (i64, f64),
+    /// Optimal `t*` and `alpha` and cost of [`estimate_attack_new`].
+    pub attack_new: (i64, i64, f64),
+    /// Relative error of each of [`Quantity::ALL`] with respect to its exact value, the costs
+    /// being the ones at the optima of the backend.
+    pub errors: [f64; 5],
+}
+
+impl Evaluation {
+    /// log2 of `quantity`.
+    pub fn log2(&self, quantity: Quantity) -> f64 {
+        match quantity {
+            Quantity::ProbBeta => self.prob_beta,
+            Quantity::ProbB => self.prob_b,
+            Quantity::ProbBNew => self.prob_b_new.1,
+            Quantity::EstimateAttack => self.attack.1,
+            Quantity::EstimateAttackNew => self.attack_new.2,
+        }
+    }
+
+    /// Relative error of `quantity` with respect to its exact value.
+    pub fn relative_error(&self, quantity: Quantity) -> f64 {
+        self.errors[quantity as usize]
+    }
+
+    /// Optimal `t*` and `alpha` of `quantity`, [`None`] if it does not choose them.
+    pub fn optimum(&self, quantity: Quantity) -> Optimum {
+        match quantity {
+            Quantity::ProbBeta | Quantity::ProbB => Optimum::default(),
+            Quantity::ProbBNew => Optimum {
+                ts: None,
+                alpha: Some(self.prob_b_new.0),
+            },
+            Quantity::EstimateAttack => Optimum {
+                ts: Some(self.attack.0),
+                alpha: None,
+            },
+            Quantity::EstimateAttackNew => Optimum {
+                ts: Some(self.attack_new.0),
+                alpha: Some(self.attack_new.1),
+            },
+        }
+    }
+}
+
+/// Optimal `t*` and `alpha` chosen by a quantity, [`None`] if it does not choose them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Optimum {
+    pub ts: Option<i64>,
+    pub alpha: Option<i64>,
+}
+
+impl fmt::Display for Optimum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.ts, self.alpha) {
+            (Some(ts), Some(alpha)) => write!(f, "t* = {ts}, alpha = {alpha}"),
+            (Some(ts), None) => write!(f, "t* = {ts}"),
+            (None, Some(alpha)) => write!(f, "alpha = {alpha}"),
+            (None, None) => write!(f, "-"),
+        }
+    }
+}
+
+/// Disagreement found by [`check_consistency`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Failure {
+    /// Relative error of `quantity` above the tolerance, or NaN.
+    Error {
+        backend: Backend,
+        quantity: Quantity,
+        error: f64,
+    },
+    /// Optimum of `quantity` different from the one of the most precise backend.
+    Optimum {
+        backend: Backend,
+        quantity: Quantity,
+        optimum: Optimum,
+        reference: Optimum,
+    },
+}
+
+/// Outcome of [`check_consistency`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    /// Number of rounds `t*` at which the probabilities are evaluated.
+    pub ts: i64,
+    /// Maximum accepted relative error.
+    pub tolerance: f64,
+    /// Evaluation of the most precise backend, whose optima are the reference.
+    pub reference: Evaluation,
+    /// Evaluations of the other enabled backends.
+    pub evaluations: Vec<Evaluation>,
+}
+
+impl Report {
+    /// Relative error of `quantity` in `evaluation` with respect to its exact value.
+    pub fn relative_error(&self, evaluation: &Evaluation, quantity: Quantity) -> f64 {
+        evaluation.relative_error(quantity)
+    }
+
+    /// Relative errors above the tolerance (or NaN) of every backend, and optima of the other
+    /// backends different from the reference.
+    pub fn failures(&self) -> Vec<Failure> {
+        let mut failures = Vec::new();
+        for evaluation in std::iter::once(&self.reference).chain(&self.evaluations) {
+            for quantity in Quantity::ALL {
+                let error = evaluation.relative_error(quantity);
+                if error.is_nan() || error > self.tolerance {
+                    failures.push(Failure::Error {
+                        backend: evaluation.backend,
+                        quantity,
+                        error,
+                    });
+                }
+                let (optimum, reference) = (
+                    evaluation.optimum(quantity),
+                    self.reference.optimum(quantity),
+                );
+                if optimum != reference {
+                    failures.push(Failure::Optimum {
+                        backend: evaluation.backend,
+                        quantity,
+                        optimum,
+                        reference,
+                    });
+                }
+            }
+        }
+        failures
+    }
+
+    /// Returns [`true`] if every backend is within the tolerance and agrees on the optima.
+    pub fn is_consistent(&self) -> bool {
+        self.failures().is_empty()
+    }
+}
+
+/// `numerator / denominator` rounded by `T`.
+pub(crate) fn ratio<T: Float>((numerator, denominator): &(BigInt, BigInt)) -> T {
+    // the quotient is scaled by 2^shift so that it keeps more bits than T, which covers the
+    // truncation; the divisions by powers of two are exact, in steps which fit in any backend
+    let precision = T::BACKEND.precision() as u64;
+    let mut shift = (denominator.bits() + precision + 64).saturating_sub(numerator.bits());
+    let mut value = T::from_bigint(&((numerator << shift) / denominator));
+    while shift > 0 {
+        let step = shift.min(512);
+        value = value / T::from_bigint(&(BigInt::from(1) << step));
+        shift -= step;
+    }
+    value
+}
+
+/// Relative error of `value` with respect to `reference`, computed by `T`.
+pub(crate) fn relative_error<T: Float>(value: &T, reference: &T) -> f64 {
+    if value == reference {
+        // also covers two zero probabilities
+        0.0
+    } else {
+        let difference = if value > reference {
+            T::zero() + value - reference
+        } else {
+            T::zero() + reference - value
+        };
+        (difference / reference).log2().exp2()
+    }
+}
+
+fn evaluate_with<T: Float>(
+    backend: Backend,
+    exact: &Exact,
+    t: i64,
+    w: i64,
+    p: i64,
+    ts: Option<i64>,
+    quiet: bool,
+) -> Evaluation {
+    let scheme = Scheme::cross(p, t, w);
+    let error = |value: &T, exact: &(BigInt, BigInt)| relative_error(value, &ratio::<T>(exact));
+
+    let attack = estimate_attack::<T>(t, w, p, quiet);
+    let attack_new = estimate_attack_new::<T>(t, w, p, quiet);
+    let ts = ts.unwrap_or(attack_new.0);
+    let beta = prob_beta::<T>(t, ts, p);
+    let b = prob_b::<T>(t, ts, w, p);
+    let (aa, b_new) = prob_b_new::<T>(t, ts, w, p);
+    // the costs at the optima, computed by the same operations as in the estimators
+    let cost = scheme::attack_cost::<T>(&scheme, attack.0);
+    let (_, cost_new) = scheme::attack_new_cost::<T>(&scheme, attack_new.0);
+
+    Evaluation {
+        backend,
+        prob_beta: beta.log2(),
+        prob_b: b.log2(),
+        prob_b_new: (aa, b_new.log2()),
+        attack,
+        attack_new,
+        errors: [
+            error(&beta, &exact.prob_beta(ts)),
+            error(&b, &exact.prob_b(ts)),
+            error(&b_new, &exact.prob_b_new(ts, aa)),
+            error(&cost, &exact.attack_cost(attack.0)),
+            error(
+                &cost_new,
+                &exact.attack_new_cost(attack_new.0, attack_new.1),
+            ),
+        ],
+    }
+}
+
+/// Evaluate all the quantities with `backend`, at `ts` or at the optimal `t*` of
+/// [`estimate_attack_new`] if [`None`].
+fn evaluate(
+    backend: Backend,
+    exact: &Exact,
+    t: i64,
+    w: i64,
+    p: i64,
+    ts: Option<i64>,
+    quiet: bool,
+) -> Evaluation {
+    crate::with_backend!(backend, T => evaluate_with::<T>(backend, exact, t, w, p, ts, quiet))
+}
+
+/// Compare the results of every enabled backend against the exact values, and their optima
+/// against the ones of the most precise backend.
+///
+/// The probabilities are evaluated at `ts`, or at the optimal `t*` of our attack according to
+/// the reference backend if [`None`]. If `quiet` is [`true`] the progress bars are hidden.
+///
+/// # Arguments
+///
+/// * `t` - Number of parallel iterations.
+/// * `w` - Fixed-weight parameter.
+/// * `p` - Characteristic of the finite field. The challenge space for the first challenge has size `p-1`.
+/// * `ts` - Number of rounds `t*` at which the probabilities are compared.
+/// * `tolerance` - Maximum accepted relative error.
+/// * `quiet` - Hide the progress bars.
+///
+/// # Panics
+///
+/// Panics if no backend is enabled or if `ts > t`.
+pub fn check_consistency(
+    t: i64,
+    w: i64,
+    p: i64,
+    ts: Option<i64>,
+    tolerance: f64,
+    quiet: bool,
+) -> Report {
+    let most_precise = Backend::most_precise().expect("no Float backend is enabled");
+    assert!(ts.is_none_or(|ts| ts <= t), "expected t* <= t");
+    let scheme = Scheme::cross(p, t, w);
+    let exact = Exact::new(&scheme);
+
+    let reference = evaluate(most_precise, &exact, t, w, p, ts, quiet);
+    let ts = ts.unwrap_or(reference.attack_new.0);
+    let evaluations = Backend::ENABLED
+        .iter()
+        .filter(|&&backend| backend != most_precise)
+        .map(|&backend| evaluate(backend, &exact, t, w, p, Some(ts), quiet))
+        .collect();
+
+    Report {
+        ts,
+        tolerance,
+        reference,
+        evaluations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_consistency, ratio, relative_error, Failure, Optimum, Quantity};
+    use crate::float::{Backend, BigInt};
+
+    #[cfg(feature = "inexact")]
+    #[test]
+    fn test_relative_error() {
+        use crate::float::{F64Num, Float};
+
+        let value = |n: i64| F64Num::from(n);
+        assert_eq!(relative_error(&value(4), &value(4)), 0.0);
+        assert_eq!(relative_error(&value(0), &value(0)), 0.0);
+        assert_eq!(relative_error(&value(0), &value(4)), 1.0);
+        assert_eq!(relative_error(&value(8), &value(4)), 1.0);
+        assert_eq!(relative_error(&value(4), &value(8)), 0.5);
+        assert!(relative_error(&(value(0) / value(0)), &value(4)).is_nan());
+
+        let third: F64Num = ratio(&(BigInt::from(1), BigInt::from(3)));
+        assert_eq!(third, value(1) / value(3));
+        let tiny: F64Num = ratio(&(BigInt::from(3), BigInt::from(2).pow(1000)));
+        assert_eq!(tiny, value(3) / value(2).pow(1000));
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let report = check_consistency(24, 18, 13, None, 1e-6, true);
+
+        assert_eq!(report.ts, report.reference.attack_new.0);
+        assert_eq!(report.evaluations.len() + 1, Backend::ENABLED.len());
+        assert!(report.is_consistent(), "{:?}", report.failures());
+
+        // a tolerance of zero flags any rounding error
+        let report = check_consistency(24, 18, 13, Some(3), 0.0, true);
+        let failures = report.failures();
+        for evaluation in std::iter::once(&report.reference).chain(&report.evaluations) {
+            for quantity in Quantity::ALL {
+                let error = report.relative_error(evaluation, quantity);
+                let failure = Failure::Error {
+                    backend: evaluation.backend,
+                    quantity,
+                    error,
+                };
+                assert_eq!(error > 0.0, failures.contains(&failure));
+            }
+        }
+
+        // the most precise backend resolves errors far below the resolution of the log2 in f64
+        let reference = &report.reference;
+        if reference.backend.precision() > 64 {
+            for quantity in Quantity::ALL {
+                assert!(reference.relative_error(quantity) < 1e-20, "{quantity}");
+            }
+        }
+
+        // an optimum different from the one of the most precise backend is a failure
+        let mut report = check_consistency(24, 18, 13, None, 1e-6, true);
+        let mut evaluation = report.reference;
+        evaluation.attack.0 += 1;
+        report.evaluations.push(evaluation);
+        assert_eq!(
+            report.failures(),
+            [Failure::Optimum {
+                backend: evaluation.backend,
+                quantity: Quantity::EstimateAttack,
+                optimum: Optimum {
+                    ts: Some(report.reference.attack.0 + 1),
+                    alpha: None
+                },
+                reference: Optimum {
+                    ts: Some(report.reference.attack.0),
+                    alpha: None
+                },
+            }]
+        );
+    }
+}
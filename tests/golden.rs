@@ -0,0 +1,129 @@
+//! Golden values of the results table of the README, for every enabled backend.
+//!
+//! Each test estimates both attacks on a parameter set of [`presets`] and compares the costs to
+//! two decimals, `t*` and `alpha` with the published ones. The fast and balanced parameter sets
+//! run by default. The small ones, with `t` close to 960, take minutes per backend and are
+//! ignored, and are skipped for `f64` whose exponent overflows:
+//!
+//! ```sh
+//! cargo test --release --test golden -- --include-ignored
+//! ```
+//!
+//! [`presets`]: revise_cross_parameters::presets
+
+/// Row of the results table: costs of the original attack and of ours with their optima.
+struct Row {
+    cost_cross: &'static str,
+    ts_cross: i64,
+    cost_our: &'static str,
+    ts_our: i64,
+    alpha: i64,
+}
+
+fn check<T: revise_cross_parameters::float::Float>(name: &str, row: Row) {
+    let preset = revise_cross_parameters::presets::find(name).unwrap();
+    let (p, t, w) = (preset.p, preset.t, preset.w);
+
+    let (ts, cost) = revise_cross_parameters::estimate_attack::<T>(t, w, p, true);
+    assert_eq!(
+        (format!("{:.2}", cost), ts),
+        (row.cost_cross.to_string(), row.ts_cross),
+        "original attack on {name}"
+    );
+
+    let (ts, alpha, cost) = revise_cross_parameters::estimate_attack_new::<T>(t, w, p, true);
+    assert_eq!(
+        (format!("{:.2}", cost), ts, alpha),
+        (row.cost_our.to_string(), row.ts_our, row.alpha),
+        "our attack on {name}"
+    );
+}
+
+/// Golden tests of `$type`, one for each row.
+macro_rules! golden {
+    ($type:ty; $($(#[$attr:meta])* $test:ident: $preset:literal, $cost_cross:literal, $ts_cross:literal, $cost_our:literal, $ts_our:literal, $alpha:literal;)*) => {
+        $(
+            #[test]
+            $(#[$attr])*
+            fn $test() {
+                check::<$type>(
+                    $preset,
+                    Row {
+                        cost_cross: $cost_cross,
+                        ts_cross: $ts_cross,
+                        cost_our: $cost_our,
+                        ts_our: $ts_our,
+                        alpha: $alpha,
+                    },
+                );
+            }
+        )*
+    };
+}
+
+/// Golden tests of `$type` on the small parameter sets, unless `$sets` is `fast`.
+macro_rules! small_golden {
+    ($type:ty, fast) => {};
+    ($type:ty, all) => {
+        golden! {
+            $type;
+            #[ignore = "slow"]
+            rsdp_1_small: "CROSS-R-SDP-1-small", "128.00", 65, "97.48", 55, 960;
+            #[ignore = "slow"]
+            rsdp_3_small: "CROSS-R-SDP-3-small", "192.02", 83, "156.37", 73, 944;
+            #[ignore = "slow"]
+            rsdp_5_small: "CROSS-R-SDP-5-small", "255.22", 101, "217.15", 91, 957;
+            #[ignore = "slow"]
+            rsdp_g_1_small: "CROSS-R-SDP-G-1-small", "128.01", 38, "108.22", 34, 867;
+            #[ignore = "slow"]
+            rsdp_g_3_small: "CROSS-R-SDP-G-3-small", "192.03", 53, "167.56", 48, 937;
+            #[ignore = "slow"]
+            rsdp_g_5_small: "CROSS-R-SDP-G-5-small", "256.03", 66, "228.58", 61, 974;
+        }
+    };
+}
+
+macro_rules! float_test {
+    ($name:ident: $type:ty, $sets:ident) => {
+        mod $name {
+            use crate::{check, Row};
+
+            golden! {
+                $type;
+                rsdp_1_fast: "CROSS-R-SDP-1-fast", "128.06", 35, "128.05", 35, 86;
+                rsdp_1_balanced: "CROSS-R-SDP-1-balanced", "128.01", 40, "120.46", 38, 227;
+                rsdp_3_fast: "CROSS-R-SDP-3-fast", "192.08", 52, "192.05", 52, 128;
+                rsdp_3_balanced: "CROSS-R-SDP-3-balanced", "192.07", 61, "179.67", 59, 365;
+                rsdp_5_fast: "CROSS-R-SDP-5-fast", "256.06", 70, "256.03", 70, 171;
+                rsdp_5_balanced: "CROSS-R-SDP-5-balanced", "256.01", 81, "240.82", 78, 459;
+                rsdp_g_1_fast: "CROSS-R-SDP-G-1-fast", "128.06", 24, "128.06", 24, 79;
+                rsdp_g_1_balanced: "CROSS-R-SDP-G-1-balanced", "128.13", 27, "122.72", 26, 216;
+                rsdp_g_3_fast: "CROSS-R-SDP-G-3-fast", "192.03", 37, "191.98", 37, 125;
+                rsdp_g_3_balanced: "CROSS-R-SDP-G-3-balanced", "192.03", 37, "189.83", 37, 184;
+                rsdp_g_5_fast: "CROSS-R-SDP-G-5-fast", "256.01", 49, "256.00", 49, 158;
+                rsdp_g_5_balanced: "CROSS-R-SDP-G-5-balanced", "256.08", 51, "252.70", 50, 270;
+            }
+
+            small_golden! { $type, $sets }
+        }
+    };
+}
+
+macro_rules! tests {
+    ($(#[cfg($meta:meta)] $name:ident: $type:ty, $sets:ident,)*) => {
+        $(
+            #[cfg($meta)]
+            float_test! { $name: $type, $sets }
+        )*
+    };
+}
+
+tests! {
+    // f64 overflows on the small parameter sets
+    #[cfg(feature = "inexact")] f64: revise_cross_parameters::float::F64Num, fast,
+    #[cfg(feature = "inexact")] ext_f64: revise_cross_parameters::float::ExtF64Num, all,
+    #[cfg(feature = "rug")] rug: revise_cross_parameters::float::RugNum, all,
+    #[cfg(feature = "dashu")] dashu: revise_cross_parameters::float::DashuNum, all,
+    #[cfg(feature = "nightly-float")] f128: revise_cross_parameters::float::F128Num, all,
+    #[cfg(feature = "double-double")] dd: revise_cross_parameters::float::DdNum, all,
+}
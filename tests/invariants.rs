@@ -0,0 +1,108 @@
+//! Invariants of the probabilities and of `binom` over random small parameters, for every enabled
+//! backend.
+//!
+//! They guard the bounds of the inner sums of `prob_b` and `prob_b_new`, whose terms would
+//! otherwise silently vanish or be counted twice.
+
+use proptest::prelude::*;
+
+/// Largest difference in bits between two probabilities that are equal in exact arithmetic.
+const TOLERANCE: f64 = 1e-6;
+
+/// Small CROSS parameters `(p, t, w, t*)`.
+fn params() -> impl Strategy<Value = (i64, i64, i64, i64)> {
+    (3..=1024i64, 1..=40i64).prop_flat_map(|(p, t)| (Just(p), Just(t), 0..=t, 0..=t))
+}
+
+macro_rules! float_test {
+    ($name:ident: $type:ty) => {
+        mod $name {
+            use super::{params, TOLERANCE};
+            use proptest::prelude::*;
+            use revise_cross_parameters::float::Float;
+            use revise_cross_parameters::{binom, prob_b, prob_b_new, prob_b_new_alpha, prob_beta};
+
+            /// Asserts that `prob` is a probability, up to the rounding errors.
+            fn assert_probability(prob: &$type) -> Result<(), TestCaseError> {
+                prop_assert!(!prob.is_nan());
+                prop_assert!(*prob >= <$type>::from(0));
+                prop_assert!(prob.log2() <= TOLERANCE, "{} > 1", prob.log2());
+                Ok(())
+            }
+
+            proptest! {
+                #[test]
+                fn test_prob_beta_non_increasing((p, t, _, ts) in params()) {
+                    let prob = prob_beta::<$type>(t, ts, p);
+                    assert_probability(&prob)?;
+                    if ts == 0 {
+                        prop_assert!(prob.log2().abs() <= TOLERANCE);
+                    }
+                    if ts < t {
+                        let next = prob_beta::<$type>(t, ts + 1, p);
+                        prop_assert!(next.log2() <= prob.log2() + TOLERANCE);
+                    }
+                }
+
+                #[test]
+                fn test_prob_b_bounds((p, t, w, ts) in params()) {
+                    assert_probability(&prob_b::<$type>(t, ts, w, p))?;
+
+                    let (alpha, prob) = prob_b_new::<$type>(t, ts, w, p);
+                    prop_assert!((w..=t).contains(&alpha));
+                    assert_probability(&prob)?;
+                }
+
+                #[test]
+                fn test_prob_b_new_at_w((p, t, w, ts) in params()) {
+                    let prob = prob_b::<$type>(t, ts, w, p);
+                    let prob_at_w = prob_b_new_alpha::<$type>(t, ts, w, p, w);
+                    prop_assert!(
+                        (prob.log2() - prob_at_w.log2()).abs() <= TOLERANCE
+                            || prob == <$type>::from(0) && prob_at_w == <$type>::from(0),
+                        "{} != {}",
+                        prob.log2(),
+                        prob_at_w.log2()
+                    );
+
+                    // the optimal alpha is at least as good as alpha = w
+                    let (_, prob_new) = prob_b_new::<$type>(t, ts, w, p);
+                    prop_assert!(prob_new.log2() >= prob.log2() - TOLERANCE);
+                }
+
+                #[test]
+                fn test_binom(n in 1..=50i64, k in -2..=52i64) {
+                    let value = binom::<$type>(n, k);
+                    if !(0..=n).contains(&k) {
+                        prop_assert!(value == <$type>::from(0));
+                        return Ok(());
+                    }
+
+                    let symmetric = binom::<$type>(n, n - k);
+                    prop_assert!((value.log2() - symmetric.log2()).abs() <= TOLERANCE);
+
+                    let pascal = binom::<$type>(n - 1, k - 1) + binom::<$type>(n - 1, k);
+                    prop_assert!((value.log2() - pascal.log2()).abs() <= TOLERANCE);
+                }
+            }
+        }
+    };
+}
+
+macro_rules! tests {
+    ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+        $(
+            #[cfg($meta)]
+            float_test! { $name: $type }
+        )*
+    };
+}
+
+tests! {
+    #[cfg(feature = "inexact")] f64: revise_cross_parameters::float::F64Num,
+    #[cfg(feature = "inexact")] ext_f64: revise_cross_parameters::float::ExtF64Num,
+    #[cfg(feature = "rug")] rug: revise_cross_parameters::float::RugNum,
+    #[cfg(feature = "dashu")] dashu: revise_cross_parameters::float::DashuNum,
+    #[cfg(feature = "nightly-float")] f128: revise_cross_parameters::float::F128Num,
+    #[cfg(feature = "double-double")] dd: revise_cross_parameters::float::DdNum,
+}
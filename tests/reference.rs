@@ -0,0 +1,139 @@
+//! Differential tests against the Decimal implementation of `scripts/attack.ipynb`, for every
+//! enabled backend.
+//!
+//! `data/reference.json` holds the probabilities for every `t*` and the optima of both attacks on
+//! a grid of small parameters, as computed by `scripts/reference.py`. Although the notebook sums
+//! `prob_b_new` over `ws` up to `min(t - j, alpha)` instead of `min(t - j, w)`, the extra terms
+//! contain `binom(j, w - ws)` with `ws > w` and vanish, so that both must agree. When several
+//! values of `alpha` are optimal, the notebook returns the smallest and the library the largest
+//! one, which is accepted as long as both give the same probability.
+
+use revise_cross_parameters::float::Float;
+use revise_cross_parameters::{
+    estimate_attack, estimate_attack_new, prob_b, prob_b_new, prob_b_new_alpha, prob_beta,
+};
+use serde_json::Value;
+
+/// Largest accepted difference in bits between the reference and the library.
+const TOLERANCE: f64 = 1e-6;
+
+/// Compares the library with the reference using `T`, returning the discrepancies.
+fn compare<T: Float>() -> Vec<String> {
+    let reference: Value = serde_json::from_str(include_str!("data/reference.json")).unwrap();
+    let mut failures = Vec::new();
+    let mut check = |case: &str, quantity: String, expected: f64, actual: f64| {
+        if (expected - actual).abs() > TOLERANCE {
+            failures.push(format!(
+                "{case}: {quantity} is {actual}, expected {expected}"
+            ));
+        }
+    };
+
+    for case in reference["cases"].as_array().unwrap() {
+        let [p, t, w] = ["p", "t", "w"].map(|name| case[name].as_i64().unwrap());
+        let name = format!("p = {p}, t = {t}, w = {w}");
+
+        for ts in 0..=t {
+            let i = ts as usize;
+            let expected = case["prob_beta"][i].as_f64().unwrap();
+            let actual = prob_beta::<T>(t, ts, p).log2();
+            check(
+                &name,
+                format!("log2 prob_beta at t* = {ts}"),
+                expected,
+                actual,
+            );
+
+            let expected = case["prob_b"][i].as_f64().unwrap();
+            let actual = prob_b::<T>(t, ts, w, p).log2();
+            check(&name, format!("log2 prob_b at t* = {ts}"), expected, actual);
+
+            let expected_alpha = case["prob_b_new"][i][0].as_i64().unwrap();
+            let expected = case["prob_b_new"][i][1].as_f64().unwrap();
+            let (alpha, prob) = prob_b_new::<T>(t, ts, w, p);
+            check(
+                &name,
+                format!("log2 prob_b_new at t* = {ts}"),
+                expected,
+                prob.log2(),
+            );
+            // on ties the notebook keeps the first optimal alpha and the library the last one
+            if alpha != expected_alpha {
+                let actual = prob_b_new_alpha::<T>(t, ts, w, p, expected_alpha).log2();
+                check(
+                    &name,
+                    format!("log2 prob_b_new at t* = {ts} and alpha = {expected_alpha}"),
+                    expected,
+                    actual,
+                );
+            }
+        }
+
+        let expected = &case["attack"];
+        let (ts, cost) = estimate_attack::<T>(t, w, p, true);
+        check(
+            &name,
+            "t* of the original attack".to_string(),
+            expected["ts"].as_f64().unwrap(),
+            ts as f64,
+        );
+        check(
+            &name,
+            "cost of the original attack".to_string(),
+            expected["cost"].as_f64().unwrap(),
+            cost,
+        );
+
+        let expected = &case["attack_new"];
+        let (ts, alpha, cost) = estimate_attack_new::<T>(t, w, p, true);
+        check(
+            &name,
+            "t* of our attack".to_string(),
+            expected["ts"].as_f64().unwrap(),
+            ts as f64,
+        );
+        check(
+            &name,
+            "alpha of our attack".to_string(),
+            expected["alpha"].as_f64().unwrap(),
+            alpha as f64,
+        );
+        check(
+            &name,
+            "cost of our attack".to_string(),
+            expected["cost"].as_f64().unwrap(),
+            cost,
+        );
+    }
+    failures
+}
+
+macro_rules! float_test {
+    ($name:ident: $type:ty) => {
+        mod $name {
+            #[test]
+            fn test_reference() {
+                let failures = crate::compare::<$type>();
+                assert!(failures.is_empty(), "{}", failures.join("\n"));
+            }
+        }
+    };
+}
+
+macro_rules! tests {
+    ($(#[cfg($meta:meta)] $name:ident: $type:ty,)*) => {
+        $(
+            #[cfg($meta)]
+            float_test! { $name: $type }
+        )*
+    };
+}
+
+tests! {
+    #[cfg(feature = "inexact")] f64: revise_cross_parameters::float::F64Num,
+    #[cfg(feature = "inexact")] ext_f64: revise_cross_parameters::float::ExtF64Num,
+    #[cfg(feature = "rug")] rug: revise_cross_parameters::float::RugNum,
+    #[cfg(feature = "dashu")] dashu: revise_cross_parameters::float::DashuNum,
+    #[cfg(feature = "nightly-float")] f128: revise_cross_parameters::float::F128Num,
+    #[cfg(feature = "double-double")] dd: revise_cross_parameters::float::DdNum,
+}